@@ -0,0 +1,24 @@
+//! With the `capi` feature, generates the C header from `src/capi.rs` and
+//! `cbindgen.toml` into `OUT_DIR`. `tests/capi.rs` checks that
+//! `capi/hydroperx_utf16.h` matches it.
+
+fn main() {
+    #[cfg(feature = "capi")]
+    {
+        use std::{env, path::Path};
+
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let header = Path::new(&env::var("OUT_DIR").unwrap()).join("hydroperx_utf16.h");
+        let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src(crate_dir.join("src/capi.rs"))
+            .generate()
+            .expect("cannot generate the C header")
+            .write_to_file(&header);
+        println!("cargo::rustc-env=HYDROPERX_UTF16_HEADER={}", header.display());
+        println!("cargo::rerun-if-changed=src/capi.rs");
+        println!("cargo::rerun-if-changed=cbindgen.toml");
+    }
+    println!("cargo::rerun-if-changed=build.rs");
+}
\ No newline at end of file
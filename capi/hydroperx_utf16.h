@@ -0,0 +1,128 @@
+/* C API of the hydroperx-utf16 crate, enabled by the `capi` feature.
+   Generated from src/capi.rs by cbindgen; see build.rs. */
+
+#ifndef HYDROPERX_UTF16_H
+#define HYDROPERX_UTF16_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+/* An owned UTF-16 string. Handles must be freed with utf16_string_free. */
+typedef struct Utf16String Utf16String;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Creates an empty string.
+ */
+Utf16String *utf16_string_new(void);
+
+/*
+ Creates a string by copying code units.
+
+ # Safety
+
+ `data` must be valid for reading `len` code units.
+ */
+Utf16String *utf16_string_from_utf16(const uint16_t *data, size_t len);
+
+/*
+ Creates a string from UTF-8 bytes, or returns null if they are not
+ valid UTF-8.
+
+ # Safety
+
+ `data` must be valid for reading `len` bytes.
+ */
+Utf16String *utf16_string_from_utf8(const char *data, size_t len);
+
+/*
+ Frees a string. Null is ignored.
+
+ # Safety
+
+ `string` must be null or a handle returned by this API that has not
+ been freed.
+ */
+void utf16_string_free(Utf16String *string);
+
+/*
+ Returns the number of code units.
+
+ # Safety
+
+ `string` must be a live handle.
+ */
+size_t utf16_string_len(const Utf16String *string);
+
+/*
+ Returns a pointer to the code units, valid until the string is modified
+ or freed. The code units are not NUL-terminated.
+
+ # Safety
+
+ `string` must be a live handle.
+ */
+const uint16_t *utf16_string_data(const Utf16String *string);
+
+/*
+ Appends code units.
+
+ # Safety
+
+ `string` must be a live handle and `data` must be valid for reading
+ `len` code units that do not overlap the string.
+ */
+void utf16_string_append_utf16(Utf16String *string, const uint16_t *data, size_t len);
+
+/*
+ Appends UTF-8 bytes, returning false without modifying the string if
+ they are not valid UTF-8.
+
+ # Safety
+
+ `string` must be a live handle and `data` must be valid for reading
+ `len` bytes.
+ */
+bool utf16_string_append_utf8(Utf16String *string, const char *data, size_t len);
+
+/*
+ Copies the code units in `start..end` into a new string, or returns
+ null if the range is out of bounds.
+
+ # Safety
+
+ `string` must be a live handle.
+ */
+Utf16String *utf16_string_slice(const Utf16String *string, size_t start, size_t end);
+
+/*
+ Converts into NUL-terminated UTF-8, replacing lone surrogates with
+ U+FFFD. The length excluding the terminator is written to `len`.
+
+ The result must be freed with [`utf16_string_free_utf8`].
+
+ # Safety
+
+ `string` must be a live handle and `len` must be valid for writing.
+ */
+char *utf16_string_to_utf8(const Utf16String *string, size_t *len);
+
+/*
+ Frees UTF-8 returned by [`utf16_string_to_utf8`]. Null is ignored.
+
+ # Safety
+
+ `data` must be null or returned by `utf16_string_to_utf8` with the
+ length `len`, and not freed since.
+ */
+void utf16_string_free_utf8(char *data, size_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* HYDROPERX_UTF16_H */
@@ -0,0 +1,46 @@
+/* Exercises the C API; run by tests/capi.rs. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "hydroperx_utf16.h"
+
+#define CHECK(cond) do { if (!(cond)) { fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); return 1; } } while (0)
+
+int main(void) {
+    Utf16String *string = utf16_string_from_utf8("ab", 2);
+    CHECK(string != NULL);
+    const uint16_t crab[] = { 0xD83E, 0xDD80 };
+    utf16_string_append_utf16(string, crab, 2);
+    CHECK(utf16_string_append_utf8(string, "c", 1));
+    CHECK(!utf16_string_append_utf8(string, "\xFF", 1));
+    CHECK(utf16_string_len(string) == 5);
+    CHECK(utf16_string_data(string)[2] == 0xD83E);
+
+    Utf16String *slice = utf16_string_slice(string, 1, 4);
+    CHECK(slice != NULL);
+    CHECK(utf16_string_slice(string, 4, 6) == NULL);
+
+    size_t len;
+    char *utf8 = utf16_string_to_utf8(slice, &len);
+    CHECK(len == 5);
+    CHECK(strcmp(utf8, "b\xF0\x9F\xA6\x80") == 0);
+    utf16_string_free_utf8(utf8, len);
+
+    Utf16String *lone = utf16_string_new();
+    utf16_string_append_utf16(lone, crab, 1);
+    utf8 = utf16_string_to_utf8(lone, &len);
+    CHECK(strcmp(utf8, "\xEF\xBF\xBD") == 0);
+    utf16_string_free_utf8(utf8, len);
+
+    CHECK(utf16_string_from_utf8("\xC0", 1) == NULL);
+    Utf16String *empty = utf16_string_from_utf16(NULL, 0);
+    CHECK(utf16_string_len(empty) == 0);
+
+    utf16_string_free(empty);
+    utf16_string_free(lone);
+    utf16_string_free(slice);
+    utf16_string_free(string);
+    utf16_string_free(NULL);
+    return 0;
+}
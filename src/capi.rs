@@ -0,0 +1,162 @@
+//! C ABI, enabled by the `capi` feature.
+//!
+//! The functions operate on heap-allocated [`Utf16String`] handles and are
+//! declared in `capi/hydroperx_utf16.h`. Code units are shared with C
+//! through [`utf16_string_data`] without converting through UTF-8.
+//!
+//! The header is generated from this file by cbindgen with `cbindgen.toml`
+//! (see `build.rs`), and `tests/capi.rs` fails when it is out of date.
+//!
+//! Passing a null handle to a function that expects one is undefined
+//! behavior. A null data pointer is accepted when its length is zero.
+
+use core::{ffi::c_char, ptr, slice};
+use alloc::{borrow::ToOwned, boxed::Box, string::String};
+
+use crate::Utf16String;
+
+unsafe fn code_units<'a>(data: *const u16, len: usize) -> &'a [u16] {
+    if len == 0 { &[] } else { unsafe { slice::from_raw_parts(data, len) } }
+}
+
+unsafe fn utf8<'a>(data: *const c_char, len: usize) -> Option<&'a str> {
+    let bytes = if len == 0 { &[] } else { unsafe { slice::from_raw_parts(data as *const u8, len) } };
+    core::str::from_utf8(bytes).ok()
+}
+
+/// Creates an empty string.
+#[unsafe(no_mangle)]
+pub extern "C" fn utf16_string_new() -> *mut Utf16String {
+    Box::into_raw(Box::new(Utf16String::new()))
+}
+
+/// Creates a string by copying code units.
+///
+/// # Safety
+///
+/// `data` must be valid for reading `len` code units.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn utf16_string_from_utf16(data: *const u16, len: usize) -> *mut Utf16String {
+    let buf = unsafe { code_units(data, len) }.to_vec();
+    Box::into_raw(Box::new(Utf16String { buf }))
+}
+
+/// Creates a string from UTF-8 bytes, or returns null if they are not
+/// valid UTF-8.
+///
+/// # Safety
+///
+/// `data` must be valid for reading `len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn utf16_string_from_utf8(data: *const c_char, len: usize) -> *mut Utf16String {
+    match unsafe { utf8(data, len) } {
+        Some(string) => Box::into_raw(Box::new(Utf16String::from(string))),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Frees a string. Null is ignored.
+///
+/// # Safety
+///
+/// `string` must be null or a handle returned by this API that has not
+/// been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn utf16_string_free(string: *mut Utf16String) {
+    if !string.is_null() {
+        drop(unsafe { Box::from_raw(string) });
+    }
+}
+
+/// Returns the number of code units.
+///
+/// # Safety
+///
+/// `string` must be a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn utf16_string_len(string: *const Utf16String) -> usize {
+    unsafe { (*string).len() }
+}
+
+/// Returns a pointer to the code units, valid until the string is modified
+/// or freed. The code units are not NUL-terminated.
+///
+/// # Safety
+///
+/// `string` must be a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn utf16_string_data(string: *const Utf16String) -> *const u16 {
+    unsafe { (*string).as_ptr() }
+}
+
+/// Appends code units.
+///
+/// # Safety
+///
+/// `string` must be a live handle and `data` must be valid for reading
+/// `len` code units that do not overlap the string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn utf16_string_append_utf16(string: *mut Utf16String, data: *const u16, len: usize) {
+    unsafe { (*string).buf.extend_from_slice(code_units(data, len)) }
+}
+
+/// Appends UTF-8 bytes, returning false without modifying the string if
+/// they are not valid UTF-8.
+///
+/// # Safety
+///
+/// `string` must be a live handle and `data` must be valid for reading
+/// `len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn utf16_string_append_utf8(string: *mut Utf16String, data: *const c_char, len: usize) -> bool {
+    match unsafe { utf8(data, len) } {
+        Some(other) => {
+            unsafe { (*string).push_utf8_str(other) };
+            true
+        }
+        None => false,
+    }
+}
+
+/// Copies the code units in `start..end` into a new string, or returns
+/// null if the range is out of bounds.
+///
+/// # Safety
+///
+/// `string` must be a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn utf16_string_slice(string: *const Utf16String, start: usize, end: usize) -> *mut Utf16String {
+    match unsafe { (*string).get(start..end) } {
+        Some(slice) => Box::into_raw(Box::new(slice.to_owned())),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Converts into NUL-terminated UTF-8, replacing lone surrogates with
+/// U+FFFD. The length excluding the terminator is written to `len`.
+///
+/// The result must be freed with [`utf16_string_free_utf8`].
+///
+/// # Safety
+///
+/// `string` must be a live handle and `len` must be valid for writing.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn utf16_string_to_utf8(string: *const Utf16String, len: *mut usize) -> *mut c_char {
+    let mut bytes = String::from_utf16_lossy(unsafe { &(*string).buf }).into_bytes();
+    unsafe { *len = bytes.len() };
+    bytes.push(0);
+    Box::into_raw(bytes.into_boxed_slice()) as *mut c_char
+}
+
+/// Frees UTF-8 returned by [`utf16_string_to_utf8`]. Null is ignored.
+///
+/// # Safety
+///
+/// `data` must be null or returned by `utf16_string_to_utf8` with the
+/// length `len`, and not freed since.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn utf16_string_free_utf8(data: *mut c_char, len: usize) {
+    if !data.is_null() {
+        drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(data as *mut u8, len + 1)) });
+    }
+}
\ No newline at end of file
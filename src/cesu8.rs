@@ -0,0 +1,188 @@
+//! CESU-8 and Java's modified UTF-8.
+//!
+//! Both encodings write every UTF-16 code unit on its own in one to three
+//! bytes, so a supplementary code point takes six bytes and lone
+//! surrogates round-trip exactly. Modified UTF-8, used by class files,
+//! JNI and `DataOutput.writeUTF`, also encodes NUL as `C0 80` so that the
+//! output never contains a zero byte.
+//!
+//! ```
+//! use hydroperx_utf16::{Utf16String, cesu8::*};
+//!
+//! let string = Utf16String::from("a\0\u{10000}");
+//! let bytes = encode_modified_utf8(&string);
+//! assert_eq!(bytes, b"a\xC0\x80\xED\xA0\x80\xED\xB0\x80");
+//! assert_eq!(decode_modified_utf8(&bytes).unwrap(), string);
+//!
+//! let mut out = vec![];
+//! write_utf(&string, &mut out).unwrap();
+//! let (decoded, rest) = read_utf(&out).unwrap();
+//! assert_eq!(decoded, string);
+//! assert!(rest.is_empty());
+//! ```
+
+use core::fmt;
+use alloc::vec::Vec;
+
+use crate::{Utf16Str, Utf16String};
+
+/// Maximum number of bytes that `write_utf` can frame.
+pub const MAX_UTF_LEN: usize = u16::MAX as usize;
+
+/// Encodes a string into CESU-8.
+pub fn encode_cesu8(string: &Utf16Str) -> Vec<u8> {
+    encode(string, false)
+}
+
+/// Encodes a string into Java's modified UTF-8.
+pub fn encode_modified_utf8(string: &Utf16Str) -> Vec<u8> {
+    encode(string, true)
+}
+
+/// Returns the number of bytes needed to encode a string into CESU-8.
+pub fn cesu8_len(string: &Utf16Str) -> usize {
+    string.raw.iter().map(|cu| encoded_len(*cu, false)).sum()
+}
+
+/// Returns the number of bytes needed to encode a string into Java's
+/// modified UTF-8.
+pub fn modified_utf8_len(string: &Utf16Str) -> usize {
+    string.raw.iter().map(|cu| encoded_len(*cu, true)).sum()
+}
+
+/// Decodes CESU-8. Four-byte sequences and the `C0 80` form of NUL are
+/// rejected.
+pub fn decode_cesu8(bytes: &[u8]) -> Result<Utf16String, Cesu8Error> {
+    decode(bytes, false)
+}
+
+/// Decodes Java's modified UTF-8. Four-byte sequences and zero bytes are
+/// rejected.
+pub fn decode_modified_utf8(bytes: &[u8]) -> Result<Utf16String, Cesu8Error> {
+    decode(bytes, true)
+}
+
+/// Appends a string in the framing of `DataOutput.writeUTF`: a big-endian
+/// `u16` byte length followed by modified UTF-8.
+///
+/// Fails with [`Cesu8Error::TooLong`] if the encoded string exceeds
+/// [`MAX_UTF_LEN`] bytes, leaving `out` unchanged.
+pub fn write_utf(string: &Utf16Str, out: &mut Vec<u8>) -> Result<(), Cesu8Error> {
+    let len = modified_utf8_len(string);
+    if len > MAX_UTF_LEN {
+        return Err(Cesu8Error::TooLong(len));
+    }
+    out.reserve(2 + len);
+    out.extend_from_slice(&(len as u16).to_be_bytes());
+    encode_into(string, true, out);
+    Ok(())
+}
+
+/// Reads a string in the framing of `DataInput.readUTF`, returning it
+/// together with the input that follows it.
+///
+/// Offsets in errors are relative to the start of `input`.
+pub fn read_utf(input: &[u8]) -> Result<(Utf16String, &[u8]), Cesu8Error> {
+    let [hi, lo, rest @ ..] = input else {
+        return Err(Cesu8Error::UnexpectedEnd(0));
+    };
+    let len = u16::from_be_bytes([*hi, *lo]) as usize;
+    if rest.len() < len {
+        return Err(Cesu8Error::UnexpectedEnd(0));
+    }
+    let string = decode(&rest[..len], true).map_err(|error| match error {
+        Cesu8Error::InvalidSequence(offset) => Cesu8Error::InvalidSequence(offset + 2),
+        Cesu8Error::UnexpectedEnd(offset) => Cesu8Error::UnexpectedEnd(offset + 2),
+        error => error,
+    })?;
+    Ok((string, &rest[len..]))
+}
+
+fn encoded_len(cu: u16, modified: bool) -> usize {
+    match cu {
+        0 if modified => 2,
+        0..=0x7F => 1,
+        0x80..=0x7FF => 2,
+        _ => 3,
+    }
+}
+
+fn encode(string: &Utf16Str, modified: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(string.len());
+    encode_into(string, modified, &mut out);
+    out
+}
+
+fn encode_into(string: &Utf16Str, modified: bool, out: &mut Vec<u8>) {
+    for &cu in &string.raw {
+        match cu {
+            0 if modified => out.extend_from_slice(&[0xC0, 0x80]),
+            0..=0x7F => out.push(cu as u8),
+            0x80..=0x7FF => out.extend_from_slice(&[0xC0 | (cu >> 6) as u8, 0x80 | (cu & 0x3F) as u8]),
+            _ => out.extend_from_slice(&[
+                0xE0 | (cu >> 12) as u8,
+                0x80 | ((cu >> 6) & 0x3F) as u8,
+                0x80 | (cu & 0x3F) as u8,
+            ]),
+        }
+    }
+}
+
+fn decode(bytes: &[u8], modified: bool) -> Result<Utf16String, Cesu8Error> {
+    let mut buf = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let continuation = |n: usize| -> Result<u16, Cesu8Error> {
+            match bytes.get(i + n) {
+                Some(c) if c & 0xC0 == 0x80 => Ok((c & 0x3F) as u16),
+                Some(_) => Err(Cesu8Error::InvalidSequence(i)),
+                None => Err(Cesu8Error::UnexpectedEnd(i)),
+            }
+        };
+        let (cu, len) = match b {
+            0 if modified => return Err(Cesu8Error::InvalidSequence(i)),
+            0..=0x7F => (b as u16, 1),
+            0xC0 if modified => match continuation(1)? {
+                0 => (0, 2),
+                _ => return Err(Cesu8Error::InvalidSequence(i)),
+            },
+            0xC2..=0xDF => (((b & 0x1F) as u16) << 6 | continuation(1)?, 2),
+            0xE0..=0xEF => {
+                let cu = ((b & 0x0F) as u16) << 12 | continuation(1)? << 6 | continuation(2)?;
+                if cu < 0x800 {
+                    return Err(Cesu8Error::InvalidSequence(i));
+                }
+                (cu, 3)
+            }
+            _ => return Err(Cesu8Error::InvalidSequence(i)),
+        };
+        buf.push(cu);
+        i += len;
+    }
+    Ok(Utf16String { buf })
+}
+
+/// Error produced when encoding or decoding CESU-8 or modified UTF-8.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Cesu8Error {
+    /// The byte sequence starting at the offset is not valid.
+    InvalidSequence(usize),
+    /// The input ends inside the sequence starting at the offset.
+    UnexpectedEnd(usize),
+    /// The encoded string takes this many bytes, more than `write_utf`
+    /// can frame.
+    TooLong(usize),
+}
+
+impl fmt::Display for Cesu8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cesu8Error::InvalidSequence(offset) => write!(f, "invalid byte sequence at offset {offset}"),
+            Cesu8Error::UnexpectedEnd(offset) => write!(f, "unexpected end of input at offset {offset}"),
+            Cesu8Error::TooLong(len) => write!(f, "encoded string of {len} bytes exceeds {MAX_UTF_LEN} bytes"),
+        }
+    }
+}
+
+impl core::error::Error for Cesu8Error {}
\ No newline at end of file
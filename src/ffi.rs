@@ -0,0 +1,382 @@
+//! NUL-terminated UTF-16 strings for passing to C `char16_t*` APIs.
+//!
+//! [`Utf16CStr`] and [`Utf16CString`] are the UTF-16 counterparts of
+//! `CStr` and `CString`: they hold code units followed by exactly one NUL
+//! terminator and no interior NUL.
+//!
+//! ```
+//! use hydroperx_utf16::ffi::*;
+//!
+//! let string = Utf16CString::new("hello").unwrap();
+//! let ptr = string.as_ptr();
+//! let borrowed = unsafe { Utf16CStr::from_ptr(ptr) };
+//! assert_eq!(borrowed.as_utf16_str(), "hello");
+//! assert_eq!(borrowed.to_utf16_with_nul().len(), 6);
+//!
+//! assert!(Utf16CString::new("a\0b").is_err());
+//! ```
+
+use core::fmt;
+use core::ops::Deref;
+use alloc::borrow::{Borrow, ToOwned};
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::{Utf16Str, Utf16String};
+
+/// A borrowed NUL-terminated UTF-16 string.
+///
+/// The terminator is not part of the [`Utf16Str`] returned by
+/// [`as_utf16_str`](Self::as_utf16_str).
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
+pub struct Utf16CStr {
+    /// Code units including the terminator.
+    raw: [u16],
+}
+
+/// An owned NUL-terminated UTF-16 string.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Utf16CString {
+    /// Code units including the terminator.
+    inner: Box<[u16]>,
+}
+
+impl Utf16CStr {
+    /// Wraps a raw NUL-terminated string, scanning for its terminator.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null, aligned and point to code units that are
+    /// valid for reads up to and including a NUL terminator. The memory
+    /// must not be mutated for the lifetime `'a`.
+    pub unsafe fn from_ptr<'a>(ptr: *const u16) -> &'a Self {
+        let mut len = 0;
+        unsafe {
+            while *ptr.add(len) != 0 {
+                len += 1;
+            }
+            Self::from_utf16_with_nul_unchecked(core::slice::from_raw_parts(ptr, len + 1))
+        }
+    }
+
+    /// Wraps code units that end with a NUL terminator and contain no
+    /// interior NUL.
+    pub fn from_utf16_with_nul(raw: &[u16]) -> Result<&Self, FromUtf16WithNulError> {
+        check_with_nul(raw)?;
+        Ok(unsafe { Self::from_utf16_with_nul_unchecked(raw) })
+    }
+
+    /// Wraps code units without checking for the NUL terminator.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must end with a NUL terminator and contain no interior NUL.
+    pub unsafe fn from_utf16_with_nul_unchecked(raw: &[u16]) -> &Self {
+        unsafe { &*(raw as *const [u16] as *const Self) }
+    }
+
+    /// Returns a pointer to the string, valid while `self` is alive.
+    #[inline]
+    pub fn as_ptr(&self) -> *const u16 {
+        self.raw.as_ptr()
+    }
+
+    /// Returns the number of code units, excluding the terminator.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.raw.len() - 1
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the string without the terminator.
+    #[inline]
+    pub fn as_utf16_str(&self) -> &Utf16Str {
+        unsafe { Utf16Str::from_utf16_unchecked(&self.raw[..self.raw.len() - 1]) }
+    }
+
+    /// Returns the code units including the terminator.
+    #[inline]
+    pub fn to_utf16_with_nul(&self) -> &[u16] {
+        &self.raw
+    }
+}
+
+impl Utf16CString {
+    /// Creates a NUL-terminated string, failing if the string contains a NUL.
+    pub fn new(string: impl Into<Utf16String>) -> Result<Self, NulError> {
+        let string = string.into();
+        match string.raw.iter().position(|cu| *cu == 0) {
+            Some(position) => Err(NulError(position, string)),
+            None => Ok(unsafe { Self::from_vec_unchecked(string.buf) }),
+        }
+    }
+
+    /// Appends a terminator to code units without checking for interior NUL.
+    ///
+    /// # Safety
+    ///
+    /// `vec` must not contain NUL.
+    pub unsafe fn from_vec_unchecked(mut vec: Vec<u16>) -> Self {
+        vec.reserve_exact(1);
+        vec.push(0);
+        Self { inner: vec.into_boxed_slice() }
+    }
+
+    /// Takes code units that end with a NUL terminator and contain no
+    /// interior NUL.
+    pub fn from_vec_with_nul(vec: Vec<u16>) -> Result<Self, FromVecWithNulError> {
+        match check_with_nul(&vec) {
+            Ok(()) => Ok(unsafe { Self::from_vec_with_nul_unchecked(vec) }),
+            Err(error) => Err(FromVecWithNulError { error, vec }),
+        }
+    }
+
+    /// Takes code units without checking for the NUL terminator.
+    ///
+    /// # Safety
+    ///
+    /// `vec` must end with a NUL terminator and contain no interior NUL.
+    pub unsafe fn from_vec_with_nul_unchecked(vec: Vec<u16>) -> Self {
+        Self { inner: vec.into_boxed_slice() }
+    }
+
+    /// Transfers ownership of the string to a C caller.
+    ///
+    /// The pointer must be returned to Rust through
+    /// [`from_raw`](Self::from_raw) to be freed.
+    pub fn into_raw(self) -> *mut u16 {
+        Box::into_raw(self.inner) as *mut u16
+    }
+
+    /// Retakes ownership of a string returned by [`into_raw`](Self::into_raw).
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `into_raw` and not freed since, and
+    /// its terminator must not have been moved by the C side.
+    pub unsafe fn from_raw(ptr: *mut u16) -> Self {
+        unsafe {
+            let len = Utf16CStr::from_ptr(ptr).raw.len();
+            Self {
+                inner: Box::from_raw(core::ptr::slice_from_raw_parts_mut(ptr, len)),
+            }
+        }
+    }
+
+    #[inline]
+    pub fn as_utf16_cstr(&self) -> &Utf16CStr {
+        unsafe { Utf16CStr::from_utf16_with_nul_unchecked(&self.inner) }
+    }
+
+    /// Converts into a string, dropping the terminator.
+    pub fn into_utf16_string(self) -> Utf16String {
+        let mut buf = self.into_vec_with_nul();
+        buf.pop();
+        Utf16String { buf }
+    }
+
+    /// Converts into code units including the terminator.
+    pub fn into_vec_with_nul(self) -> Vec<u16> {
+        self.inner.into_vec()
+    }
+}
+
+fn check_with_nul(raw: &[u16]) -> Result<(), FromUtf16WithNulError> {
+    match raw.iter().position(|cu| *cu == 0) {
+        Some(position) if position + 1 == raw.len() => Ok(()),
+        Some(position) => Err(FromUtf16WithNulError::InteriorNul(position)),
+        None => Err(FromUtf16WithNulError::NotNulTerminated),
+    }
+}
+
+impl Default for &Utf16CStr {
+    fn default() -> Self {
+        unsafe { Utf16CStr::from_utf16_with_nul_unchecked(&[0]) }
+    }
+}
+
+impl Default for Utf16CString {
+    fn default() -> Self {
+        Self { inner: Box::new([0]) }
+    }
+}
+
+impl Deref for Utf16CString {
+    type Target = Utf16CStr;
+
+    #[inline]
+    fn deref(&self) -> &Utf16CStr {
+        self.as_utf16_cstr()
+    }
+}
+
+impl Borrow<Utf16CStr> for Utf16CString {
+    #[inline]
+    fn borrow(&self) -> &Utf16CStr {
+        self
+    }
+}
+
+impl ToOwned for Utf16CStr {
+    type Owned = Utf16CString;
+
+    fn to_owned(&self) -> Utf16CString {
+        Utf16CString { inner: self.raw.into() }
+    }
+}
+
+impl AsRef<Utf16CStr> for Utf16CStr {
+    #[inline]
+    fn as_ref(&self) -> &Utf16CStr {
+        self
+    }
+}
+
+impl AsRef<Utf16CStr> for Utf16CString {
+    #[inline]
+    fn as_ref(&self) -> &Utf16CStr {
+        self
+    }
+}
+
+impl AsRef<Utf16Str> for Utf16CStr {
+    #[inline]
+    fn as_ref(&self) -> &Utf16Str {
+        self.as_utf16_str()
+    }
+}
+
+impl AsRef<Utf16Str> for Utf16CString {
+    #[inline]
+    fn as_ref(&self) -> &Utf16Str {
+        self.as_utf16_str()
+    }
+}
+
+impl From<&Utf16CStr> for Utf16CString {
+    fn from(value: &Utf16CStr) -> Self {
+        value.to_owned()
+    }
+}
+
+impl From<&Utf16CStr> for Utf16String {
+    fn from(value: &Utf16CStr) -> Self {
+        value.as_utf16_str().to_owned()
+    }
+}
+
+impl From<Utf16CString> for Utf16String {
+    fn from(value: Utf16CString) -> Self {
+        value.into_utf16_string()
+    }
+}
+
+impl TryFrom<Utf16String> for Utf16CString {
+    type Error = NulError;
+
+    fn try_from(value: Utf16String) -> Result<Self, NulError> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<&Utf16Str> for Utf16CString {
+    type Error = NulError;
+
+    fn try_from(value: &Utf16Str) -> Result<Self, NulError> {
+        Self::new(value)
+    }
+}
+
+impl fmt::Debug for Utf16CStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_utf16_str(), f)
+    }
+}
+
+impl fmt::Debug for Utf16CString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_utf16_str(), f)
+    }
+}
+
+/// Error produced when code units are not a valid NUL-terminated string.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum FromUtf16WithNulError {
+    /// A NUL was found before the last code unit.
+    InteriorNul(usize),
+    /// The last code unit is not NUL.
+    NotNulTerminated,
+}
+
+impl fmt::Display for FromUtf16WithNulError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FromUtf16WithNulError::InteriorNul(position) => write!(f, "interior NUL found at code unit {position}"),
+            FromUtf16WithNulError::NotNulTerminated => write!(f, "string is not NUL-terminated"),
+        }
+    }
+}
+
+impl core::error::Error for FromUtf16WithNulError {}
+
+/// Error produced by [`Utf16CString::from_vec_with_nul`], holding the
+/// rejected code units.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FromVecWithNulError {
+    error: FromUtf16WithNulError,
+    vec: Vec<u16>,
+}
+
+impl FromVecWithNulError {
+    #[inline]
+    pub fn error(&self) -> FromUtf16WithNulError {
+        self.error
+    }
+
+    #[inline]
+    pub fn as_utf16(&self) -> &[u16] {
+        &self.vec
+    }
+
+    #[inline]
+    pub fn into_vec(self) -> Vec<u16> {
+        self.vec
+    }
+}
+
+impl fmt::Display for FromVecWithNulError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl core::error::Error for FromVecWithNulError {}
+
+/// Error produced by [`Utf16CString::new`] when the string contains a NUL,
+/// holding the position of the NUL and the rejected string.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NulError(usize, Utf16String);
+
+impl NulError {
+    #[inline]
+    pub fn nul_position(&self) -> usize {
+        self.0
+    }
+
+    #[inline]
+    pub fn into_utf16_string(self) -> Utf16String {
+        self.1
+    }
+}
+
+impl fmt::Display for NulError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NUL found at code unit {}", self.0)
+    }
+}
+
+impl core::error::Error for NulError {}
\ No newline at end of file
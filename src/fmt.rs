@@ -0,0 +1,81 @@
+use crate::{Utf16Str, Utf16String};
+use core::fmt::{self, Write};
+use alloc::string::String;
+
+impl fmt::Display for Utf16Str {
+    /// Writes the string, replacing lone surrogates with U+FFFD.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chars = char::decode_utf16(self.raw.iter().copied()).map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER));
+        if f.width().is_none() && f.precision().is_none() {
+            for ch in chars {
+                f.write_char(ch)?;
+            }
+            Ok(())
+        } else {
+            f.pad(&chars.collect::<String>())
+        }
+    }
+}
+
+impl fmt::Debug for Utf16Str {
+    /// Writes the string as a quoted literal, escaping lone surrogates
+    /// as `\u{D800}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for ch in char::decode_utf16(self.raw.iter().copied()) {
+            match ch {
+                Ok('\'') => f.write_char('\'')?,
+                Ok(ch) => write!(f, "{}", ch.escape_debug())?,
+                Err(error) => write!(f, "\\u{{{:X}}}", error.unpaired_surrogate())?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+impl fmt::Display for Utf16String {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl fmt::Debug for Utf16String {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Write for Utf16String {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_utf8_str(s);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
+/// Creates a `Utf16String` using interpolation of runtime expressions,
+/// as `format!` does for `String`.
+///
+/// ```
+/// use hydroperx_utf16::utf16_format;
+///
+/// let string = utf16_format!("{}-{}", 1, "\u{10000}");
+/// assert_eq!(string, "1-\u{10000}");
+/// ```
+#[macro_export]
+macro_rules! utf16_format {
+    ($($arg:tt)*) => {{
+        let mut r = $crate::Utf16String::new();
+        ::core::fmt::Write::write_fmt(&mut r, ::core::format_args!($($arg)*))
+            .expect("a formatting trait implementation returned an error");
+        r
+    }};
+}
\ No newline at end of file
@@ -0,0 +1,258 @@
+use crate::{utils::*, Utf16Str, Utf16String};
+use core::fmt;
+use alloc::{borrow::ToOwned, vec, vec::Vec};
+
+/// Minimum number of code units allocated when the gap is full.
+const MIN_GAP: usize = 64;
+
+/// A UTF-16 string with a gap at the cursor, where insertion and deletion
+/// take amortized O(1).
+///
+/// Moving the cursor by `n` code units takes O(n). The gap never splits a
+/// surrogate pair; if an edit joins a high and a low surrogate around the
+/// cursor, the cursor moves past the pair.
+#[derive(Clone)]
+pub struct Utf16GapBuffer {
+    buf: Vec<u16>,
+    gap_start: usize,
+    gap_end: usize,
+}
+
+impl Utf16GapBuffer {
+    pub fn new() -> Self {
+        Utf16GapBuffer {
+            buf: vec![],
+            gap_start: 0,
+            gap_end: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Utf16GapBuffer {
+            buf: vec![0; capacity],
+            gap_start: 0,
+            gap_end: capacity,
+        }
+    }
+
+    /// Returns the number of UTF-16 code units, excluding the gap.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buf.len() - (self.gap_end - self.gap_start)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the cursor offset in code units.
+    #[inline]
+    pub fn cursor(&self) -> usize {
+        self.gap_start
+    }
+
+    /// Moves the cursor to an offset in code units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is out of bounds or splits a surrogate pair.
+    pub fn set_cursor(&mut self, offset: usize) {
+        assert!(offset <= self.len(), "cursor out of bounds of Utf16GapBuffer");
+        let (before, after) = self.halves();
+        let splits_pair = if offset < before.len() {
+            offset > 0 && is_high_surrogate(before.raw[offset - 1]) && is_low_surrogate(before.raw[offset])
+        } else {
+            let i = offset - before.len();
+            let prev = if i == 0 { before.raw.last() } else { after.raw.get(i - 1) };
+            prev.is_some_and(|cu| is_high_surrogate(*cu)) && after.raw.get(i).is_some_and(|cu| is_low_surrogate(*cu))
+        };
+        assert!(!splits_pair, "cursor splits a surrogate pair");
+        self.move_gap(offset);
+    }
+
+    /// Returns the text before and after the cursor.
+    #[inline]
+    pub fn halves(&self) -> (&Utf16Str, &Utf16Str) {
+        unsafe {
+            (
+                Utf16Str::from_utf16_unchecked(&self.buf[..self.gap_start]),
+                Utf16Str::from_utf16_unchecked(&self.buf[self.gap_end..]),
+            )
+        }
+    }
+
+    /// Moves the cursor to the end, returning the whole text as one slice.
+    pub fn make_contiguous(&mut self) -> &mut Utf16Str {
+        let len = self.len();
+        self.move_gap(len);
+        unsafe { Utf16Str::from_utf16_unchecked_mut(&mut self.buf[..len]) }
+    }
+
+    pub fn insert(&mut self, ch: char) {
+        let mut units = [0; 2];
+        self.insert_code_units(ch.encode_utf16(&mut units));
+    }
+
+    pub fn insert_utf16_str(&mut self, string: &Utf16Str) {
+        self.insert_code_units(&string.raw);
+    }
+
+    pub fn insert_utf8_str(&mut self, string: &str) {
+        // a UTF-8 string never has more code units than bytes
+        self.reserve(string.len());
+        for cu in string.encode_utf16() {
+            self.buf[self.gap_start] = cu;
+            self.gap_start += 1;
+        }
+        self.normalize();
+    }
+
+    /// Removes the code point before the cursor and returns it. A lone
+    /// surrogate is returned as U+FFFD.
+    pub fn delete_backward(&mut self) -> Option<char> {
+        let cu2 = *self.buf[..self.gap_start].last()?;
+        let ch = if self.gap_start > 1 && is_low_surrogate(cu2) && is_high_surrogate(self.buf[self.gap_start - 2]) {
+            self.gap_start -= 2;
+            decode_char(self.buf[self.gap_start], cu2)
+        } else {
+            self.gap_start -= 1;
+            code_unit_to_char(cu2)
+        };
+        self.normalize();
+        Some(ch)
+    }
+
+    /// Removes the code point after the cursor and returns it. A lone
+    /// surrogate is returned as U+FFFD.
+    pub fn delete_forward(&mut self) -> Option<char> {
+        let cu1 = *self.buf.get(self.gap_end)?;
+        let ch = if self.buf.get(self.gap_end + 1).is_some_and(|cu| is_high_surrogate(cu1) && is_low_surrogate(*cu)) {
+            self.gap_end += 2;
+            decode_char(cu1, self.buf[self.gap_end - 1])
+        } else {
+            self.gap_end += 1;
+            code_unit_to_char(cu1)
+        };
+        self.normalize();
+        Some(ch)
+    }
+
+    pub fn clear(&mut self) {
+        self.gap_start = 0;
+        self.gap_end = self.buf.len();
+    }
+
+    /// Reserves room in the gap for at least `additional` code units.
+    pub fn reserve(&mut self, additional: usize) {
+        let gap = self.gap_end - self.gap_start;
+        if gap >= additional {
+            return;
+        }
+        let new_gap = additional.max(self.len()).max(MIN_GAP);
+        let after = self.buf.len() - self.gap_end;
+        self.buf.resize(self.gap_start + new_gap + after, 0);
+        let new_gap_end = self.buf.len() - after;
+        self.buf.copy_within(self.gap_end..self.gap_end + after, new_gap_end);
+        self.gap_end = new_gap_end;
+    }
+
+    pub fn to_utf16_string(&self) -> Utf16String {
+        let (before, after) = self.halves();
+        let mut buf = Vec::with_capacity(self.len());
+        buf.extend_from_slice(&before.raw);
+        buf.extend_from_slice(&after.raw);
+        Utf16String { buf }
+    }
+
+    pub fn into_utf16_string(mut self) -> Utf16String {
+        let len = self.len();
+        self.move_gap(len);
+        self.buf.truncate(len);
+        Utf16String { buf: self.buf }
+    }
+
+    fn insert_code_units(&mut self, units: &[u16]) {
+        self.reserve(units.len());
+        self.buf[self.gap_start..self.gap_start + units.len()].copy_from_slice(units);
+        self.gap_start += units.len();
+        self.normalize();
+    }
+
+    fn move_gap(&mut self, offset: usize) {
+        if offset < self.gap_start {
+            let count = self.gap_start - offset;
+            self.buf.copy_within(offset..self.gap_start, self.gap_end - count);
+            self.gap_start = offset;
+            self.gap_end -= count;
+        } else if offset > self.gap_start {
+            let count = offset - self.gap_start;
+            self.buf.copy_within(self.gap_end..self.gap_end + count, self.gap_start);
+            self.gap_start += count;
+            self.gap_end += count;
+        }
+    }
+
+    /// Moves the cursor past a surrogate pair split by the gap.
+    fn normalize(&mut self) {
+        if self.gap_start > 0
+            && self.gap_end < self.buf.len()
+            && is_high_surrogate(self.buf[self.gap_start - 1])
+            && is_low_surrogate(self.buf[self.gap_end])
+        {
+            self.move_gap(self.gap_start + 1);
+        }
+    }
+}
+
+impl Default for Utf16GapBuffer {
+    fn default() -> Self {
+        Utf16GapBuffer::new()
+    }
+}
+
+impl From<Utf16String> for Utf16GapBuffer {
+    /// Places the cursor at the end of the string.
+    fn from(value: Utf16String) -> Self {
+        let len = value.len();
+        Utf16GapBuffer {
+            buf: value.buf,
+            gap_start: len,
+            gap_end: len,
+        }
+    }
+}
+
+impl From<&Utf16Str> for Utf16GapBuffer {
+    /// Places the cursor at the end of the string.
+    fn from(value: &Utf16Str) -> Self {
+        Utf16GapBuffer::from(value.to_owned())
+    }
+}
+
+impl From<&str> for Utf16GapBuffer {
+    /// Places the cursor at the end of the string.
+    fn from(value: &str) -> Self {
+        Utf16GapBuffer::from(Utf16String::from(value))
+    }
+}
+
+impl From<Utf16GapBuffer> for Utf16String {
+    fn from(value: Utf16GapBuffer) -> Self {
+        value.into_utf16_string()
+    }
+}
+
+impl fmt::Display for Utf16GapBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (before, after) = self.halves();
+        fmt::Display::fmt(before, f)?;
+        fmt::Display::fmt(after, f)
+    }
+}
+
+impl fmt::Debug for Utf16GapBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_utf16_string(), f)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,90 @@
+use crate::{utils::*, Utf16Str, Utf16String};
+use core::hash::{Hash, Hasher};
+
+/// Number of code units passed to `Hasher::write` at once.
+const HASH_BLOCK: usize = 32;
+
+impl Hash for Utf16Str {
+    /// Hashes the length in code units, then the code units in fixed-size
+    /// blocks, so that [`CodePointHash`] can produce the same hash from a
+    /// `str`.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for block in self.raw.chunks(HASH_BLOCK) {
+            state.write(code_unit_bytes(block));
+        }
+    }
+}
+
+impl Hash for Utf16String {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+fn code_unit_bytes(units: &[u16]) -> &[u8] {
+    unsafe { core::slice::from_raw_parts(units.as_ptr() as *const u8, units.len() * 2) }
+}
+
+/// A `str` that hashes as an equal [`Utf16Str`] does, for looking up
+/// UTF-16 keys by UTF-8 strings without converting them.
+///
+/// With the `hashbrown` feature, it implements `hashbrown::Equivalent` for
+/// [`Utf16String`], [`Utf16Str`] and [`SmallUtf16String`](crate::SmallUtf16String).
+///
+/// ```
+/// use hydroperx_utf16::{CodePointHash, Utf16String};
+/// use std::hash::{BuildHasher, RandomState};
+///
+/// let state = RandomState::new();
+/// let key = Utf16String::from("length");
+/// assert_eq!(state.hash_one(&key), state.hash_one(CodePointHash("length")));
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CodePointHash<'a>(pub &'a str);
+
+impl Hash for CodePointHash<'_> {
+    /// Encodes into a stack buffer and writes the same length and blocks as
+    /// `Utf16Str::hash`.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.0.utf16_len());
+        let mut block = [0u16; HASH_BLOCK];
+        let mut n = 0;
+        for cu in self.0.encode_utf16() {
+            block[n] = cu;
+            n += 1;
+            if n == HASH_BLOCK {
+                state.write(code_unit_bytes(&block));
+                n = 0;
+            }
+        }
+        if n != 0 {
+            state.write(code_unit_bytes(&block[..n]));
+        }
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+impl hashbrown::Equivalent<Utf16Str> for CodePointHash<'_> {
+    #[inline]
+    fn equivalent(&self, key: &Utf16Str) -> bool {
+        *key == *self.0
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+impl hashbrown::Equivalent<Utf16String> for CodePointHash<'_> {
+    #[inline]
+    fn equivalent(&self, key: &Utf16String) -> bool {
+        *key == *self.0
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+impl hashbrown::Equivalent<crate::SmallUtf16String> for CodePointHash<'_> {
+    #[inline]
+    fn equivalent(&self, key: &crate::SmallUtf16String) -> bool {
+        *key == *self.0
+    }
+}
\ No newline at end of file
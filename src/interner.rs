@@ -0,0 +1,245 @@
+//! String interning, enabled by the `interner` feature.
+//!
+//! ```
+//! use hydroperx_utf16::{Utf16String, interner::*};
+//!
+//! let mut interner = Utf16Interner::new();
+//! let a = interner.intern(&Utf16String::from("foo"));
+//! let b = interner.intern_utf8("foo");
+//! assert_eq!(a, b);
+//! assert_eq!(interner.get_utf8("foo"), Some(a));
+//! assert_eq!(interner.resolve(a), "foo");
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::RwLock;
+
+use crate::Utf16Str;
+
+/// A compact handle to an interned string.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    #[inline]
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+
+    #[inline]
+    pub fn from_u32(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+/// Deduplicates strings into [`Symbol`] handles.
+///
+/// Strings are hashed by their UTF-16 code units, so a `&str` can be looked
+/// up without first converting it into a `Utf16String`.
+pub struct Utf16Interner {
+    strings: Vec<Box<Utf16Str>>,
+    hashes: Vec<u64>,
+    /// Open addressing table of symbol indices plus one; zero is empty.
+    table: Vec<u32>,
+    hasher: RandomState,
+}
+
+impl Utf16Interner {
+    pub fn new() -> Self {
+        Self {
+            strings: vec![],
+            hashes: vec![],
+            table: vec![],
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// Returns the number of interned strings.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Interns a string, returning its symbol.
+    pub fn intern(&mut self, string: &Utf16Str) -> Symbol {
+        let hash = self.hash(string.code_units().copied());
+        match self.find(hash, |s| s == string) {
+            Ok(symbol) => symbol,
+            Err(_) => self.insert(hash, Box::<Utf16Str>::from(string)),
+        }
+    }
+
+    /// Interns a UTF-8 string, returning its symbol. The string is only
+    /// converted if it has not been interned before.
+    pub fn intern_utf8(&mut self, string: &str) -> Symbol {
+        let hash = self.hash(string.encode_utf16());
+        match self.find(hash, |s| s == string) {
+            Ok(symbol) => symbol,
+            Err(_) => self.insert(hash, crate::Utf16String::from(string).into_boxed_utf16_str()),
+        }
+    }
+
+    /// Returns the symbol of a string if it has been interned.
+    pub fn get(&self, string: &Utf16Str) -> Option<Symbol> {
+        let hash = self.hash(string.code_units().copied());
+        self.find(hash, |s| s == string).ok()
+    }
+
+    /// Returns the symbol of a UTF-8 string if it has been interned,
+    /// without allocating.
+    pub fn get_utf8(&self, string: &str) -> Option<Symbol> {
+        let hash = self.hash(string.encode_utf16());
+        self.find(hash, |s| s == string).ok()
+    }
+
+    /// Returns the string of a symbol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the symbol was not produced by this interner.
+    #[inline]
+    pub fn resolve(&self, symbol: Symbol) -> &Utf16Str {
+        self.try_resolve(symbol).expect("symbol does not belong to this interner")
+    }
+
+    /// Returns the string of a symbol, or `None` if the symbol was not
+    /// produced by this interner.
+    #[inline]
+    pub fn try_resolve(&self, symbol: Symbol) -> Option<&Utf16Str> {
+        self.strings.get(symbol.0 as usize).map(|s| &**s)
+    }
+
+    /// Iterates the symbols and their strings in interning order.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &Utf16Str)> {
+        self.strings.iter().enumerate().map(|(i, s)| (Symbol(i as u32), &**s))
+    }
+
+    fn hash(&self, code_units: impl Iterator<Item = u16>) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+        for cu in code_units {
+            hasher.write_u16(cu);
+        }
+        hasher.finish()
+    }
+
+    /// Returns either the symbol of a matching string or the empty slot
+    /// where it would be inserted.
+    fn find(&self, hash: u64, eq: impl Fn(&Utf16Str) -> bool) -> Result<Symbol, usize> {
+        if self.table.is_empty() {
+            return Err(0);
+        }
+        let mask = self.table.len() - 1;
+        let mut slot = hash as usize & mask;
+        loop {
+            match self.table[slot] {
+                0 => return Err(slot),
+                entry => {
+                    let i = entry as usize - 1;
+                    if self.hashes[i] == hash && eq(&self.strings[i]) {
+                        return Ok(Symbol(i as u32));
+                    }
+                }
+            }
+            slot = (slot + 1) & mask;
+        }
+    }
+
+    fn insert(&mut self, hash: u64, string: Box<Utf16Str>) -> Symbol {
+        let symbol = u32::try_from(self.strings.len()).ok().filter(|i| *i < u32::MAX).expect("too many interned strings");
+        self.strings.push(string);
+        self.hashes.push(hash);
+        if self.strings.len() * 2 > self.table.len() {
+            self.grow();
+        } else {
+            let slot = self.find(hash, |_| false).unwrap_err();
+            self.table[slot] = symbol + 1;
+        }
+        Symbol(symbol)
+    }
+
+    fn grow(&mut self) {
+        let len = (self.table.len() * 2).max(16);
+        self.table = vec![0; len];
+        let mask = len - 1;
+        for (i, hash) in self.hashes.iter().enumerate() {
+            let mut slot = *hash as usize & mask;
+            while self.table[slot] != 0 {
+                slot = (slot + 1) & mask;
+            }
+            self.table[slot] = i as u32 + 1;
+        }
+    }
+}
+
+impl Default for Utf16Interner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A thread-safe [`Utf16Interner`].
+#[derive(Default)]
+pub struct SyncUtf16Interner {
+    inner: RwLock<Utf16Interner>,
+}
+
+impl SyncUtf16Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().unwrap().is_empty()
+    }
+
+    /// Interns a string, returning its symbol.
+    pub fn intern(&self, string: &Utf16Str) -> Symbol {
+        if let Some(symbol) = self.get(string) {
+            return symbol;
+        }
+        self.inner.write().unwrap().intern(string)
+    }
+
+    /// Interns a UTF-8 string, returning its symbol.
+    pub fn intern_utf8(&self, string: &str) -> Symbol {
+        if let Some(symbol) = self.get_utf8(string) {
+            return symbol;
+        }
+        self.inner.write().unwrap().intern_utf8(string)
+    }
+
+    pub fn get(&self, string: &Utf16Str) -> Option<Symbol> {
+        self.inner.read().unwrap().get(string)
+    }
+
+    pub fn get_utf8(&self, string: &str) -> Option<Symbol> {
+        self.inner.read().unwrap().get_utf8(string)
+    }
+
+    /// Returns the string of a symbol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the symbol was not produced by this interner.
+    pub fn resolve(&self, symbol: Symbol) -> &Utf16Str {
+        self.try_resolve(symbol).expect("symbol does not belong to this interner")
+    }
+
+    pub fn try_resolve(&self, symbol: Symbol) -> Option<&Utf16Str> {
+        let inner = self.inner.read().unwrap();
+        let string: *const Utf16Str = inner.try_resolve(symbol)?;
+        // Interned strings are boxed and never removed or mutated, so they
+        // live as long as the interner even after the lock is released.
+        Some(unsafe { &*string })
+    }
+}
\ No newline at end of file
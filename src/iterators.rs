@@ -1,5 +1,11 @@
 use crate::{utils::*, Utf16Str};
+use core::char::DecodeUtf16;
+use core::fmt::{self, Write};
+use core::iter::Copied;
+use core::slice;
 
+/// Iterator over the code points of a [`Utf16Str`]. Lone surrogates are
+/// replaced with U+FFFD.
 pub struct Utf16Chars<'a> {
     pub(crate) slice: &'a Utf16Str,
     pub(crate) index: usize,
@@ -20,13 +26,15 @@ impl<'a> Iterator for Utf16Chars<'a> {
                 }
             }
             self.index += 1;
-            unsafe { Some(char::from_u32_unchecked(cu1 as u32)) }
+            Some(code_unit_to_char(cu1))
         } else {
             None
         }
     }
 }
 
+/// Iterator over the code points of a [`Utf16Str`] and their code unit
+/// indices. Lone surrogates are replaced with U+FFFD.
 pub struct Utf16CharIndices<'a> {
     pub(crate) slice: &'a Utf16Str,
     pub(crate) index: usize,
@@ -47,9 +55,142 @@ impl<'a> Iterator for Utf16CharIndices<'a> {
                 }
             }
             self.index += 1;
-            unsafe { Some((i, char::from_u32_unchecked(cu1 as u32))) }
+            Some((i, code_unit_to_char(cu1)))
         } else {
             None
         }
     }
-}
\ No newline at end of file
+}
+
+/// Iterator over the escaped code points of a [`Utf16Str`], returned by
+/// [`Utf16Str::escape_debug`].
+#[derive(Clone)]
+pub struct Utf16EscapeDebug<'a>(pub(crate) Escape<'a>);
+
+/// Iterator over the escaped code points of a [`Utf16Str`], returned by
+/// [`Utf16Str::escape_default`].
+#[derive(Clone)]
+pub struct Utf16EscapeDefault<'a>(pub(crate) Escape<'a>);
+
+/// Iterator over the escaped code points of a [`Utf16Str`], returned by
+/// [`Utf16Str::escape_unicode`].
+#[derive(Clone)]
+pub struct Utf16EscapeUnicode<'a>(pub(crate) Escape<'a>);
+
+#[derive(Copy, Clone)]
+pub(crate) enum EscapeMode {
+    Debug,
+    Default,
+    Unicode,
+}
+
+#[derive(Clone)]
+pub(crate) struct Escape<'a> {
+    chars: DecodeUtf16<Copied<slice::Iter<'a, u16>>>,
+    mode: EscapeMode,
+    first: bool,
+    buf: EscapeBuf,
+}
+
+impl<'a> Escape<'a> {
+    pub(crate) fn new(slice: &'a Utf16Str, mode: EscapeMode) -> Self {
+        Escape {
+            chars: char::decode_utf16(slice.raw.iter().copied()),
+            mode,
+            first: true,
+            buf: EscapeBuf::default(),
+        }
+    }
+}
+
+impl Iterator for Escape<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        loop {
+            if let Some(ch) = self.buf.next() {
+                return Some(ch);
+            }
+            self.buf = match self.chars.next()? {
+                Ok(ch) => match self.mode {
+                    EscapeMode::Debug if self.first => EscapeBuf::from_iter(ch.escape_debug()),
+                    EscapeMode::Debug => {
+                        // `str::escape_debug` only escapes grapheme extenders
+                        // at the start of the string
+                        let mut bytes = [b'a'; 5];
+                        let len = ch.encode_utf8(&mut bytes[1..]).len();
+                        let string = core::str::from_utf8(&bytes[..1 + len]).unwrap();
+                        EscapeBuf::from_iter(string.escape_debug().skip(1))
+                    }
+                    EscapeMode::Default => EscapeBuf::from_iter(ch.escape_default()),
+                    EscapeMode::Unicode => EscapeBuf::from_iter(ch.escape_unicode()),
+                },
+                Err(error) => {
+                    let cu = error.unpaired_surrogate();
+                    let hex = (0..4).rev().map(|n| char::from_digit((cu >> (n * 4)) as u32 & 0xF, 16).unwrap().to_ascii_uppercase());
+                    EscapeBuf::from_iter("\\u{".chars().chain(hex).chain(['}']))
+                }
+            };
+            self.first = false;
+        }
+    }
+}
+
+/// Escape sequence of a single code point.
+#[derive(Copy, Clone, Default)]
+struct EscapeBuf {
+    chars: [char; 10],
+    start: u8,
+    end: u8,
+}
+
+impl EscapeBuf {
+    fn from_iter(iter: impl Iterator<Item = char>) -> Self {
+        let mut buf = EscapeBuf::default();
+        for ch in iter {
+            buf.chars[buf.end as usize] = ch;
+            buf.end += 1;
+        }
+        buf
+    }
+
+    fn next(&mut self) -> Option<char> {
+        if self.start == self.end {
+            return None;
+        }
+        self.start += 1;
+        Some(self.chars[self.start as usize - 1])
+    }
+}
+
+macro_rules! escape_iterator {
+    ($name:ident) => {
+        impl Iterator for $name<'_> {
+            type Item = char;
+
+            #[inline]
+            fn next(&mut self) -> Option<char> {
+                self.0.next()
+            }
+        }
+
+        impl fmt::Display for $name<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for ch in self.clone() {
+                    f.write_char(ch)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl fmt::Debug for $name<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&format_args!("{self}")).finish()
+            }
+        }
+    };
+}
+
+escape_iterator!(Utf16EscapeDebug);
+escape_iterator!(Utf16EscapeDefault);
+escape_iterator!(Utf16EscapeUnicode);
\ No newline at end of file
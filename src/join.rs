@@ -0,0 +1,131 @@
+use crate::{utils::*, Utf16Str, Utf16String};
+use alloc::borrow::Borrow;
+use alloc::vec::Vec;
+
+/// Concatenation of slices of strings, as `[&str]::concat` and
+/// `[&str]::join` do.
+///
+/// ```
+/// use hydroperx_utf16::{Utf16Join, Utf16String};
+///
+/// let words = [Utf16String::from("a"), Utf16String::from("b")];
+/// assert_eq!(words.concat(), "ab");
+/// assert_eq!(words.join(&Utf16String::from(", ")), "a, b");
+/// ```
+pub trait Utf16Join {
+    fn concat(&self) -> Utf16String;
+
+    fn join(&self, separator: &Utf16Str) -> Utf16String;
+}
+
+impl<S: Borrow<Utf16Str>> Utf16Join for [S] {
+    fn concat(&self) -> Utf16String {
+        self.join(Default::default())
+    }
+
+    fn join(&self, separator: &Utf16Str) -> Utf16String {
+        let len = self.iter().map(|s| s.borrow().len()).sum::<usize>() + separator.len() * self.len().saturating_sub(1);
+        let mut buf = Vec::with_capacity(len);
+        for (i, s) in self.iter().enumerate() {
+            if i != 0 {
+                buf.extend_from_slice(&separator.raw);
+            }
+            buf.extend_from_slice(&s.borrow().raw);
+        }
+        Utf16String { buf }
+    }
+}
+
+impl Utf16Str {
+    /// Returns the string repeated `n` times.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity would overflow.
+    pub fn repeat(&self, n: usize) -> Utf16String {
+        Utf16String { buf: self.raw.repeat(n) }
+    }
+}
+
+/// A piece of text in either encoding, for building a [`Utf16String`] from
+/// mixed pieces.
+///
+/// ```
+/// use hydroperx_utf16::{Utf16Piece, Utf16String};
+///
+/// let name = Utf16String::from("x");
+/// let string: Utf16String = [Utf16Piece::from("let "), (&name).into(), ';'.into()].into_iter().collect();
+/// assert_eq!(string, "let x;");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub enum Utf16Piece<'a> {
+    Str(&'a str),
+    Utf16Str(&'a Utf16Str),
+    Char(char),
+}
+
+impl Utf16Piece<'_> {
+    /// Returns the length in code units.
+    pub fn len(&self) -> usize {
+        match self {
+            Utf16Piece::Str(s) => s.utf16_len(),
+            Utf16Piece::Utf16Str(s) => s.len(),
+            Utf16Piece::Char(ch) => ch.len_utf16(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Utf16Piece::Str(s) => s.is_empty(),
+            Utf16Piece::Utf16Str(s) => s.is_empty(),
+            Utf16Piece::Char(_) => false,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Utf16Piece<'a> {
+    fn from(value: &'a str) -> Self {
+        Utf16Piece::Str(value)
+    }
+}
+
+impl<'a> From<&'a Utf16Str> for Utf16Piece<'a> {
+    fn from(value: &'a Utf16Str) -> Self {
+        Utf16Piece::Utf16Str(value)
+    }
+}
+
+impl<'a> From<&'a Utf16String> for Utf16Piece<'a> {
+    fn from(value: &'a Utf16String) -> Self {
+        Utf16Piece::Utf16Str(value)
+    }
+}
+
+impl From<char> for Utf16Piece<'_> {
+    fn from(value: char) -> Self {
+        Utf16Piece::Char(value)
+    }
+}
+
+impl<'a> FromIterator<Utf16Piece<'a>> for Utf16String {
+    fn from_iter<I: IntoIterator<Item = Utf16Piece<'a>>>(iter: I) -> Self {
+        let mut r = Utf16String::new();
+        r.extend(iter);
+        r
+    }
+}
+
+impl<'a> Extend<Utf16Piece<'a>> for Utf16String {
+    /// Reserves the total length of the pieces before copying them.
+    fn extend<I: IntoIterator<Item = Utf16Piece<'a>>>(&mut self, iter: I) {
+        let pieces: Vec<Utf16Piece<'a>> = iter.into_iter().collect();
+        self.buf.reserve(pieces.iter().map(Utf16Piece::len).sum());
+        for piece in pieces {
+            match piece {
+                Utf16Piece::Str(s) => self.buf.extend(s.encode_utf16()),
+                Utf16Piece::Utf16Str(s) => self.buf.extend_from_slice(&s.raw),
+                Utf16Piece::Char(ch) => self.buf.extend_from_slice(ch.encode_utf16(&mut [0; 2])),
+            }
+        }
+    }
+}
\ No newline at end of file
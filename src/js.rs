@@ -0,0 +1,229 @@
+//! JavaScript and JSON string literals.
+//!
+//! Escaping writes lone surrogates as `\uXXXX`, so literals round-trip
+//! strings that are not well-formed UTF-16.
+//!
+//! ```
+//! use hydroperx_utf16::{Utf16Str, Utf16String};
+//!
+//! let string = unsafe { Utf16Str::from_utf16_unchecked(&[0x61, 0x0A, 0xD800]) };
+//! assert_eq!(string.escape_json(), r#""a\n\ud800""#);
+//! assert_eq!(Utf16String::unescape_js(r#"'a\n\ud800'"#).unwrap(), string);
+//! assert_eq!(Utf16String::unescape_js(r"'\u{10000}\x41\101'").unwrap(), "\u{10000}AA");
+//! assert!(Utf16String::unescape_js_strict(r"'\101'").is_err());
+//! ```
+
+use core::fmt;
+
+use crate::{utils::*, Utf16Str, Utf16String};
+
+impl Utf16Str {
+    /// Returns the string as a double-quoted JavaScript literal.
+    ///
+    /// Quotes, backslashes, control characters, U+2028, U+2029 and lone
+    /// surrogates are escaped. Other characters are written as they are.
+    pub fn escape_js(&self) -> Utf16String {
+        escape(&self.raw, true)
+    }
+
+    /// Returns the string as a JSON string literal, escaped as
+    /// `JSON.stringify` does.
+    ///
+    /// Quotes, backslashes, control characters below U+0020 and lone
+    /// surrogates are escaped. Other characters are written as they are.
+    pub fn escape_json(&self) -> Utf16String {
+        escape(&self.raw, false)
+    }
+}
+
+impl Utf16String {
+    /// Parses a single- or double-quoted JavaScript string literal in
+    /// sloppy mode, which accepts legacy octal escapes such as `\101` and
+    /// the escapes `\8` and `\9`.
+    ///
+    /// Offsets in errors are byte offsets into `literal`.
+    pub fn unescape_js(literal: &str) -> Result<Utf16String, UnescapeError> {
+        unescape(literal, false)
+    }
+
+    /// Parses a single- or double-quoted JavaScript string literal in
+    /// strict mode, which rejects legacy octal escapes.
+    ///
+    /// Offsets in errors are byte offsets into `literal`.
+    pub fn unescape_js_strict(literal: &str) -> Result<Utf16String, UnescapeError> {
+        unescape(literal, true)
+    }
+}
+
+fn escape(raw: &[u16], js: bool) -> Utf16String {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let mut r = Utf16String::new();
+    r.buf.reserve(raw.len() + 2);
+    r.buf.push(b'"' as u16);
+    let mut i = 0;
+    while i < raw.len() {
+        let cu = raw[i];
+        i += 1;
+        if is_high_surrogate(cu) && raw.get(i).is_some_and(|cu| is_low_surrogate(*cu)) {
+            r.buf.extend_from_slice(&[cu, raw[i]]);
+            i += 1;
+            continue;
+        }
+        let short = match cu {
+            0x22 => Some('"'),
+            0x5C => Some('\\'),
+            0x08 => Some('b'),
+            0x0C => Some('f'),
+            0x0A => Some('n'),
+            0x0D => Some('r'),
+            0x09 => Some('t'),
+            0x0B if js => Some('v'),
+            // `\0` followed by a digit would be a legacy octal escape
+            0x00 if js && !raw.get(i).is_some_and(|cu| (0x30..=0x39).contains(cu)) => Some('0'),
+            _ => None,
+        };
+        if let Some(ch) = short {
+            r.buf.extend_from_slice(&[b'\\' as u16, ch as u16]);
+        } else if cu < 0x20 && js {
+            r.buf.extend([b'\\', b'x', HEX[cu as usize >> 4], HEX[cu as usize & 0xF]].map(u16::from));
+        } else if cu < 0x20 || (js && (cu == 0x2028 || cu == 0x2029)) || (0xD800..=0xDFFF).contains(&cu) {
+            r.buf.extend([b'\\', b'u'].map(u16::from));
+            r.buf.extend((0..4).rev().map(|n| HEX[(cu >> (n * 4)) as usize & 0xF] as u16));
+        } else {
+            r.buf.push(cu);
+        }
+    }
+    r.buf.push(b'"' as u16);
+    r
+}
+
+fn unescape(literal: &str, strict: bool) -> Result<Utf16String, UnescapeError> {
+    let quote = match literal.as_bytes().first() {
+        Some(q @ (b'"' | b'\'')) => *q as char,
+        _ => return Err(UnescapeError::NotQuoted),
+    };
+    let mut r = Utf16String::new();
+    r.buf.reserve(literal.len());
+    let mut chars = literal.char_indices().skip(1).peekable();
+    loop {
+        let Some((i, ch)) = chars.next() else {
+            return Err(UnescapeError::Unterminated);
+        };
+        match ch {
+            _ if ch == quote => {
+                return match chars.next() {
+                    None => Ok(r),
+                    Some((j, _)) => Err(UnescapeError::UnexpectedChar(j)),
+                };
+            }
+            '\n' | '\r' => return Err(UnescapeError::UnexpectedChar(i)),
+            '\\' => {}
+            _ => {
+                r.push(ch);
+                continue;
+            }
+        }
+        let Some((_, ch)) = chars.next() else {
+            return Err(UnescapeError::Unterminated);
+        };
+        let hex = |chars: &mut core::iter::Peekable<_>, n: usize| -> Result<u32, UnescapeError> {
+            let mut value = 0;
+            for _ in 0..n {
+                match chars.next().and_then(|(_, ch): (usize, char)| ch.to_digit(16)) {
+                    Some(digit) => value = value * 16 + digit,
+                    None => return Err(UnescapeError::InvalidEscape(i)),
+                }
+            }
+            Ok(value)
+        };
+        match ch {
+            // line continuations
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            '\r' => {
+                chars.next_if(|(_, ch)| *ch == '\n');
+            }
+            'b' => r.push('\x08'),
+            'f' => r.push('\x0C'),
+            'n' => r.push('\n'),
+            'r' => r.push('\r'),
+            't' => r.push('\t'),
+            'v' => r.push('\x0B'),
+            'x' => r.buf.push(hex(&mut chars, 2)? as u16),
+            'u' if chars.next_if(|(_, ch)| *ch == '{').is_some() => {
+                let mut value = 0u32;
+                let mut digits = 0;
+                loop {
+                    match chars.next() {
+                        Some((_, '}')) if digits > 0 => break,
+                        Some((_, ch)) if ch.is_ascii_hexdigit() => {
+                            value = value.saturating_mul(16).saturating_add(ch.to_digit(16).unwrap());
+                            digits += 1;
+                        }
+                        _ => return Err(UnescapeError::InvalidEscape(i)),
+                    }
+                }
+                if value > 0x10FFFF {
+                    return Err(UnescapeError::InvalidEscape(i));
+                }
+                push_code_point(&mut r, value);
+            }
+            'u' => r.buf.push(hex(&mut chars, 4)? as u16),
+            '0' if !chars.peek().is_some_and(|(_, ch)| ch.is_ascii_digit()) => r.push('\0'),
+            '0'..='7' => {
+                if strict {
+                    return Err(UnescapeError::OctalInStrictMode(i));
+                }
+                let mut value = ch.to_digit(8).unwrap();
+                let max_digits = if value <= 3 { 3 } else { 2 };
+                for _ in 1..max_digits {
+                    match chars.next_if(|(_, ch)| ('0'..='7').contains(ch)) {
+                        Some((_, ch)) => value = value * 8 + ch.to_digit(8).unwrap(),
+                        None => break,
+                    }
+                }
+                r.buf.push(value as u16);
+            }
+            '8' | '9' if strict => return Err(UnescapeError::OctalInStrictMode(i)),
+            _ => r.push(ch),
+        }
+    }
+}
+
+fn push_code_point(r: &mut Utf16String, value: u32) {
+    match char::from_u32(value) {
+        Some(ch) => r.push(ch),
+        // a surrogate escaped as `\u{D800}`
+        None => r.buf.push(value as u16),
+    }
+}
+
+/// Error produced when parsing a JavaScript string literal.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum UnescapeError {
+    /// The literal does not start with a quote.
+    NotQuoted,
+    /// The literal ends before its closing quote.
+    Unterminated,
+    /// An unescaped line feed or carriage return appears inside the
+    /// literal, or a character follows the closing quote, at the offset.
+    UnexpectedChar(usize),
+    /// The escape sequence starting at the offset is malformed.
+    InvalidEscape(usize),
+    /// The legacy octal escape, or `\8` or `\9`, starting at the offset is
+    /// not allowed in strict mode.
+    OctalInStrictMode(usize),
+}
+
+impl fmt::Display for UnescapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnescapeError::NotQuoted => write!(f, "string literal does not start with a quote"),
+            UnescapeError::Unterminated => write!(f, "unterminated string literal"),
+            UnescapeError::UnexpectedChar(offset) => write!(f, "unexpected character at offset {offset}"),
+            UnescapeError::InvalidEscape(offset) => write!(f, "invalid escape sequence at offset {offset}"),
+            UnescapeError::OctalInStrictMode(offset) => write!(f, "octal escape sequence at offset {offset} is not allowed in strict mode"),
+        }
+    }
+}
+
+impl core::error::Error for UnescapeError {}
\ No newline at end of file
@@ -1,16 +1,54 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod cesu8;
+pub mod ffi;
+#[cfg(feature = "interner")]
+pub mod interner;
+pub mod js;
+pub mod line_index;
+pub mod num;
+pub mod offset;
+#[cfg(target_has_atomic = "ptr")]
+pub mod rope;
 pub mod slice;
 pub mod utils;
+pub mod wtf8;
+#[cfg(feature = "serde")]
+pub mod serde;
 mod iterators;
 pub use iterators::*;
 
+mod fmt;
+mod hash;
+pub use hash::*;
 mod utf16str;
 mod utf16string;
+mod small_utf16string;
+pub use small_utf16string::*;
+mod gap_buffer;
+pub use gap_buffer::*;
+mod join;
+pub use join::*;
+mod whitespace;
+pub use whitespace::*;
 
 /// A UTF-16 string slice consisting of UCS-2 code units.
 /// 
 /// Indexing this type is equivalent to indexing UTF-16 code units (not bytes),
 /// which are represented by `u16`.
-#[derive(Debug, Eq, PartialEq, Hash)]
+///
+/// Ordering compares code units, as JavaScript's `<` operator does. This
+/// differs from the code point ordering of `str` when comparing a
+/// supplementary code point against one in the range U+E000 to U+FFFF.
+/// Use [`Utf16Str::cmp_code_points`] or [`Utf16Str::cmp_with_str`] to
+/// order strings consistently with `str`.
+#[derive(Eq, PartialEq, Ord, PartialOrd)]
 #[repr(transparent)]
 pub struct Utf16Str {
     /// UTF-16 code units.
@@ -21,7 +59,9 @@ pub struct Utf16Str {
 ///
 /// Indexing this type is equivalent to indexing UTF-16 code units (not bytes),
 /// which are represented by `u16`.
-#[derive(Debug, Eq, PartialEq, Hash)]
+///
+/// Ordering compares code units; see [`Utf16Str`].
+#[derive(Clone, Default, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Utf16String {
     /// UTF-16 code units.
     pub(crate) buf: Vec<u16>,
@@ -29,7 +69,7 @@ pub struct Utf16String {
 
 #[cfg(test)]
 mod tests {
-    use super::Utf16String;
+    use super::{Utf16Str, Utf16String};
 
     #[test]
     fn test_iter() {
@@ -84,4 +124,738 @@ mod tests {
         assert_eq!(two_utf16_offsets_as_utf8_offsets(utf8string, &utf16string, 3, 4), (5, 6));
         assert_eq!(two_utf8_offsets_as_utf16_offsets(&utf16string, utf8string, 5, 6), (3, 4));
     }
+
+    #[test]
+    fn test_line_index() {
+        use crate::line_index::*;
+
+        let utf8string = "a\u{10000}\r\nb\u{E9}\rc\n";
+        let utf16string = Utf16String::from(utf8string);
+
+        for index in [LineIndex::new(&utf16string), LineIndex::from_utf8(utf8string)] {
+            assert_eq!(index.line_count(), 4);
+            assert_eq!(index.len(OffsetUnit::Utf16), 10);
+            assert_eq!(index.len(OffsetUnit::Utf8), 13);
+            assert_eq!(index.len(OffsetUnit::Char), 9);
+
+            // 'b' is at UTF-16 offset 5, UTF-8 offset 7 and char offset 4
+            assert_eq!(index.convert_offset(5, OffsetUnit::Utf16, OffsetUnit::Utf8), Some(7));
+            assert_eq!(index.convert_offset(7, OffsetUnit::Utf8, OffsetUnit::Char), Some(4));
+            assert_eq!(index.convert_offset(4, OffsetUnit::Char, OffsetUnit::Utf16), Some(5));
+
+            // offsets inside a code point or past the end
+            assert_eq!(index.convert_offset(2, OffsetUnit::Utf16, OffsetUnit::Utf8), None);
+            assert_eq!(index.convert_offset(3, OffsetUnit::Utf8, OffsetUnit::Utf16), None);
+            assert_eq!(index.convert_offset(11, OffsetUnit::Utf16, OffsetUnit::Utf8), None);
+
+            // 'c' follows a lone '\r'
+            let c = LineColumn { line: 2, column: 0 };
+            assert_eq!(index.position(11, OffsetUnit::Utf8, OffsetUnit::Utf16), Some(c));
+            assert_eq!(index.offset(c, OffsetUnit::Utf16, OffsetUnit::Utf8), Some(11));
+
+            // '\r' after U+00E9 is at column 2 in UTF-16 and column 3 in UTF-8
+            assert_eq!(index.position(6, OffsetUnit::Utf16, OffsetUnit::Utf8), Some(LineColumn { line: 1, column: 1 }));
+            assert_eq!(index.position(7, OffsetUnit::Utf16, OffsetUnit::Utf16), Some(LineColumn { line: 1, column: 2 }));
+            assert_eq!(index.position(7, OffsetUnit::Utf16, OffsetUnit::Utf8), Some(LineColumn { line: 1, column: 3 }));
+            assert_eq!(index.offset(LineColumn { line: 1, column: 4 }, OffsetUnit::Utf16, OffsetUnit::Utf16), None);
+            assert_eq!(index.offset(LineColumn { line: 3, column: 0 }, OffsetUnit::Char, OffsetUnit::Utf16), Some(10));
+            assert_eq!(index.offset(LineColumn { line: 4, column: 0 }, OffsetUnit::Char, OffsetUnit::Utf16), None);
+        }
+    }
+
+    #[test]
+    fn test_bulk_offset_conversion() {
+        use crate::utils::*;
+
+        let utf8string = "a\u{10FFFF}b\u{E9}c";
+        let utf16string = Utf16String::from(utf8string);
+
+        let utf16offsets: Vec<_> = utf8_offsets_as_utf16_offsets(utf8string, [0, 1, 5, 5, 6, 8, 9]).collect();
+        assert_eq!(utf16offsets, [Ok(0), Ok(1), Ok(3), Ok(3), Ok(4), Ok(5), Ok(6)]);
+        let utf8offsets: Vec<_> = utf16_offsets_as_utf8_offsets(&utf16string, [0, 1, 3, 3, 4, 5, 6]).collect();
+        assert_eq!(utf8offsets, [Ok(0), Ok(1), Ok(5), Ok(5), Ok(6), Ok(8), Ok(9)]);
+
+        let utf16offsets: Vec<_> = utf8_offsets_as_utf16_offsets(utf8string, [2, 5, 1, 10]).collect();
+        assert_eq!(utf16offsets, [Err(OffsetError::NotCharBoundary(2)), Ok(3), Err(OffsetError::Unsorted(1)), Err(OffsetError::OutOfBounds(10))]);
+        let utf8offsets: Vec<_> = utf16_offsets_as_utf8_offsets(&utf16string, [2, 3, 1, 7]).collect();
+        assert_eq!(utf8offsets, [Err(OffsetError::NotCharBoundary(2)), Ok(5), Err(OffsetError::Unsorted(1)), Err(OffsetError::OutOfBounds(7))]);
+
+        // a lone surrogate counts as U+FFFD
+        let utf16string = unsafe { Utf16Str::from_utf16_unchecked(&[0xD800, 0x61]) };
+        let utf8offsets: Vec<_> = utf16_offsets_as_utf8_offsets(utf16string, [1, 2]).collect();
+        assert_eq!(utf8offsets, [Ok(3), Ok(4)]);
+    }
+
+    #[test]
+    fn test_single_encoding_offsets() {
+        use crate::utils::*;
+
+        let utf8string = "a\u{10FFFF}b\u{E9}c";
+        let utf16string = Utf16String::from(utf8string);
+
+        assert_eq!(utf16string.utf8_len(), utf8string.len());
+        assert_eq!(utf8string.utf16_len(), utf16string.len());
+
+        assert_eq!(utf16string.utf8_offset_of(3), Ok(5));
+        assert_eq!(utf16string.utf8_offset_of(2), Err(OffsetError::NotCharBoundary(2)));
+        assert_eq!(utf16string.utf8_range_of(3..5), Ok(5..8));
+        assert_eq!(utf8string.utf16_offset_of(5), Ok(3));
+        assert_eq!(utf8string.utf16_offset_of(10), Err(OffsetError::OutOfBounds(10)));
+        assert_eq!(utf8string.utf16_range_of(5..8), Ok(3..5));
+        assert_eq!(utf8string.utf16_range_of(5..10), Err(OffsetError::OutOfBounds(10)));
+    }
+
+    #[test]
+    fn test_typed_offsets() {
+        use crate::{offset::*, utils::OffsetError};
+
+        let utf8string = "a\u{10FFFF}b\u{E9}c";
+        let utf16string = Utf16String::from(utf8string);
+
+        assert_eq!(Utf16Offset(3).to_utf8(&utf16string), Ok(Utf8Offset(5)));
+        assert_eq!(Utf16Offset(3).to_char(&utf16string), Ok(CharOffset(2)));
+        assert_eq!(Utf16Offset(2).to_char(&utf16string), Err(OffsetError::NotCharBoundary(2)));
+        assert_eq!(Utf8Offset(8).to_utf16(utf8string), Ok(Utf16Offset(5)));
+        assert_eq!(Utf8Offset(8).to_char(utf8string), Ok(CharOffset(4)));
+        assert_eq!(CharOffset(2).to_utf16(&utf16string), Ok(Utf16Offset(3)));
+        assert_eq!(CharOffset(5).to_utf16(&utf16string), Ok(Utf16Offset(6)));
+        assert_eq!(CharOffset(6).to_utf16(&utf16string), Err(OffsetError::OutOfBounds(6)));
+        assert_eq!(CharOffset(5).to_utf8(utf8string), Ok(Utf8Offset(9)));
+
+        let range = Utf16Range::from_utf8(utf8string, Utf8Offset(1)..Utf8Offset(6)).unwrap();
+        assert_eq!(range, Utf16Range::new(Utf16Offset(1), Utf16Offset(4)));
+        assert_eq!(range.to_utf8(&utf16string), Ok(Utf8Offset(1)..Utf8Offset(6)));
+        assert_eq!(utf16string[range].to_utf8(), "\u{10FFFF}b");
+        assert_eq!(utf16string[Utf16Offset(4)..].to_utf8(), "\u{E9}c");
+        assert_eq!(utf16string[..Utf16Offset(1)].to_utf8(), "a");
+        assert!(utf16string.get(Utf16Offset(4)..Utf16Offset(7)).is_none());
+    }
+
+    #[test]
+    fn test_std_traits() {
+        use alloc::borrow::Cow;
+        use std::collections::HashMap;
+
+        let mut map = HashMap::new();
+        map.insert(Utf16String::from("key"), 1);
+        let key = Utf16String::from("key");
+        let key: &Utf16Str = &key;
+        assert_eq!(map.get(key), Some(&1));
+
+        let cow: Cow<Utf16Str> = Cow::Borrowed(key);
+        assert_eq!(cow.into_owned(), "key");
+
+        let string: Utf16String = ['a', '\u{10000}'].into_iter().collect();
+        assert_eq!(string, "a\u{10000}");
+        let string: Utf16String = ["a", "b"].into_iter().collect();
+        assert_eq!(string.clone() + "c", "abc");
+        let mut string = Utf16String::from('a');
+        string += "b";
+        string += key;
+        string.extend(['c']);
+        assert_eq!(string, "abkeyc");
+        assert_eq!("abc".parse::<Utf16String>().unwrap(), "abc");
+        assert_eq!(<&Utf16Str>::default().len(), 0);
+
+        // code unit ordering places U+10000 before U+FFFF
+        let (supplementary, bmp) = (Utf16String::from("\u{10000}"), Utf16String::from("\u{FFFF}"));
+        assert!(supplementary < bmp);
+    }
+
+    #[test]
+    fn test_fmt() {
+        use core::fmt::Write;
+
+        let string = Utf16String::from("a\"'\n\u{10000}");
+        assert_eq!(string.to_string(), "a\"'\n\u{10000}");
+        assert_eq!(format!("{string:?}"), "\"a\\\"'\\n\u{10000}\"");
+        assert_eq!(format!("[{:>4}]", Utf16String::from("ab")), "[  ab]");
+
+        let lone = unsafe { Utf16Str::from_utf16_unchecked(&[0x61, 0xD800]) };
+        assert_eq!(lone.to_string(), "a\u{FFFD}");
+        assert_eq!(format!("{lone:?}"), "\"a\\u{D800}\"");
+
+        let mut string = Utf16String::new();
+        let ch = '\u{10000}';
+        write!(string, "{}+{ch}", 1).unwrap();
+        assert_eq!(string, "1+\u{10000}");
+        assert_eq!(crate::utf16_format!("{string}!"), "1+\u{10000}!");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde::{Deserialize, Serialize};
+        use serde_test::{assert_de_tokens, assert_tokens, Configure, Token};
+
+        let string = Utf16String::from("a\u{10000}");
+        assert_tokens(&string.clone().readable(), &[Token::Str("a\u{10000}")]);
+        assert_tokens(&string.clone().compact(), &[
+            Token::Seq { len: Some(3) },
+            Token::U16(0x61),
+            Token::U16(0xD800),
+            Token::U16(0xDC00),
+            Token::SeqEnd,
+        ]);
+        assert_de_tokens(&string.clone().compact(), &[Token::Bytes(&[0x61, 0, 0x00, 0xD8, 0x00, 0xDC])]);
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Entry {
+            #[serde(with = "crate::serde::lossless")]
+            lossless: Utf16String,
+            #[serde(with = "crate::serde::utf16le_bytes")]
+            bytes: Utf16String,
+        }
+
+        let lone = unsafe { Utf16Str::from_utf16_unchecked(&[0xD800]) }.to_owned();
+        assert_tokens(&Entry { lossless: lone.clone(), bytes: lone }.readable(), &[
+            Token::Struct { name: "Entry", len: 2 },
+            Token::Str("lossless"),
+            Token::Seq { len: Some(1) },
+            Token::U16(0xD800),
+            Token::SeqEnd,
+            Token::Str("bytes"),
+            Token::Bytes(&[0x00, 0xD8]),
+            Token::StructEnd,
+        ]);
+    }
+
+    #[test]
+    fn test_smart_pointers() {
+        use std::{rc::Rc, sync::Arc};
+
+        let string = Utf16String::from("a\u{10000}");
+        let boxed = string.clone().into_boxed_utf16_str();
+        assert_eq!(&*boxed, &string);
+        assert_eq!(boxed.clone().into_utf16_string(), string);
+        assert_eq!(Utf16String::from(boxed), string);
+
+        let rc = Rc::<Utf16Str>::from(&*string);
+        let arc = Arc::<Utf16Str>::from(string.clone());
+        assert_eq!(&*rc, &*arc);
+        assert_eq!(rc.len(), 3);
+        assert!(Box::<Utf16Str>::default().is_empty());
+    }
+
+    #[cfg(feature = "interner")]
+    #[test]
+    fn test_interner() {
+        use crate::interner::*;
+
+        let mut interner = Utf16Interner::new();
+        let symbols: Vec<_> = (0..100).map(|i| interner.intern_utf8(&format!("s{i}"))).collect();
+        assert_eq!(interner.len(), 100);
+        for (i, symbol) in symbols.iter().enumerate() {
+            let string = Utf16String::from(format!("s{i}"));
+            assert_eq!(interner.intern(&string), *symbol);
+            assert_eq!(interner.get_utf8(&format!("s{i}")), Some(*symbol));
+            assert_eq!(interner.resolve(*symbol), string);
+        }
+        assert_eq!(interner.len(), 100);
+        assert_eq!(interner.get_utf8("missing"), None);
+        assert_eq!(interner.try_resolve(Symbol::from_u32(100)), None);
+
+        let interner = SyncUtf16Interner::new();
+        let a = std::thread::scope(|scope| {
+            scope.spawn(|| interner.intern_utf8("\u{10000}")).join().unwrap()
+        });
+        assert_eq!(interner.intern(&Utf16String::from("\u{10000}")), a);
+        assert_eq!(interner.resolve(a), "\u{10000}");
+    }
+
+    #[test]
+    fn test_small_string() {
+        use crate::SmallUtf16String;
+
+        let mut string = SmallUtf16String::from("abc");
+        assert!(string.is_inline());
+        string.insert(1, '\u{10000}');
+        string.push_utf8_str("defgh");
+        assert_eq!(string, "a\u{10000}bcdefgh");
+        assert_eq!(string.len(), 10);
+        assert!(string.is_inline());
+        string.insert_utf8_str(0, "xy");
+        assert!(!string.is_inline());
+        assert_eq!(string, "xya\u{10000}bcdefgh");
+        assert_eq!(string.remove(3), '\u{10000}');
+        assert_eq!(string.pop(), Some('h'));
+        string.shrink_to_fit();
+        assert!(string.is_inline());
+        assert_eq!(string, "xyabcdefg");
+        assert_eq!(string.into_utf16_string(), Utf16String::from("xyabcdefg"));
+
+        let mut string = SmallUtf16String::from("\u{10000}");
+        assert_eq!(string.pop(), Some('\u{10000}'));
+        assert_eq!(string.pop(), None);
+        assert_eq!(core::mem::size_of::<SmallUtf16String>(), core::mem::size_of::<Utf16String>());
+    }
+
+    #[test]
+    fn test_rope() {
+        use crate::{line_index::*, rope::Utf16Rope};
+
+        let pieces = ["a", "\u{10000}", "\r", "\n", "\u{E9}", "xyz", "\r\n"];
+        let mut seed = 7u32;
+        let mut random = move |n: usize| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 16) as usize % n
+        };
+
+        let mut rope = Utf16Rope::new();
+        let mut expected = Utf16String::new();
+        let mut snapshot = (rope.clone(), expected.clone());
+        for step in 0..2000 {
+            if random(3) == 0 && !expected.is_empty() {
+                let start = random(expected.len());
+                let end = (start + random(40)).min(expected.len());
+                rope.remove(start..end);
+                expected.buf.drain(start..end);
+            } else {
+                let offset = random(expected.len() + 1);
+                let mut string = Utf16String::new();
+                for _ in 0..random(60) {
+                    string.push_utf8_str(pieces[random(pieces.len())]);
+                }
+                if random(10) == 0 {
+                    string.buf.push(0xD800);
+                }
+                rope.insert(offset, &string);
+                expected.insert_utf16_str(offset, &string);
+            }
+            if step == 1000 {
+                snapshot = (rope.clone(), expected.clone());
+            }
+        }
+        assert_eq!(rope.to_utf16_string(), expected);
+        assert_eq!(snapshot.0.to_utf16_string(), snapshot.1);
+
+        let index = LineIndex::new(&expected);
+        assert_eq!(rope.len(), expected.len());
+        assert_eq!(rope.utf8_len(), index.len(OffsetUnit::Utf8));
+        assert_eq!(rope.char_count(), index.len(OffsetUnit::Char));
+        assert_eq!(rope.line_count(), index.line_count());
+        for offset in 0..=expected.len() {
+            let utf8 = index.convert_offset(offset, OffsetUnit::Utf16, OffsetUnit::Utf8);
+            assert_eq!(rope.convert_offset(offset, OffsetUnit::Utf16, OffsetUnit::Utf8), utf8);
+            if let Some(utf8) = utf8 {
+                assert_eq!(rope.convert_offset(utf8, OffsetUnit::Utf8, OffsetUnit::Char), index.convert_offset(utf8, OffsetUnit::Utf8, OffsetUnit::Char));
+                assert_eq!(rope.line_of(offset, OffsetUnit::Utf16), index.position(offset, OffsetUnit::Utf16, OffsetUnit::Utf16).map(|p| p.line));
+            }
+        }
+        for line in 0..=index.line_count() {
+            assert_eq!(rope.line_start(line, OffsetUnit::Utf8), index.line_start(line, OffsetUnit::Utf8));
+        }
+        assert_eq!(rope.slice(3..20).to_utf16_string(), expected[3..20]);
+    }
+
+    #[test]
+    fn test_gap_buffer() {
+        use super::Utf16GapBuffer;
+
+        let mut buffer = Utf16GapBuffer::from("hello world");
+        buffer.set_cursor(5);
+        buffer.insert(',');
+        buffer.insert_utf8_str(" 🦀");
+        assert_eq!(buffer.cursor(), 9);
+        let (before, after) = buffer.halves();
+        assert_eq!(before, "hello, 🦀");
+        assert_eq!(after, " world");
+        assert_eq!(buffer.delete_backward(), Some('🦀'));
+        assert_eq!(buffer.delete_forward(), Some(' '));
+        assert_eq!(buffer.to_string(), "hello, world");
+
+        // deleting the character between a high and a low surrogate joins them
+        // into a pair, and the cursor moves past it
+        let mut buffer = Utf16GapBuffer::from(Utf16String::from("\u{10000}"));
+        buffer.set_cursor(2);
+        buffer.insert('x');
+        let units: Vec<u16> = buffer.to_utf16_string().code_units().copied().collect();
+        assert_eq!(units, [0xD800, 0xDC00, 0x78]);
+        let mut buffer = Utf16GapBuffer::new();
+        buffer.insert_utf16_str(unsafe { Utf16Str::from_utf16_unchecked(&[0xD800, 0x78, 0xDC00]) });
+        buffer.set_cursor(2);
+        assert_eq!(buffer.delete_backward(), Some('x'));
+        assert_eq!(buffer.cursor(), 2);
+        assert_eq!(buffer.halves().1.len(), 0);
+        assert_eq!(buffer.make_contiguous(), "\u{10000}");
+
+        let mut buffer = Utf16GapBuffer::new();
+        for _ in 0..500 {
+            buffer.set_cursor(buffer.len() / 6 * 3);
+            buffer.insert_utf8_str("a🦀");
+        }
+        assert_eq!(buffer.len(), 1500);
+        assert_eq!(buffer.into_utf16_string().chars().count(), 1000);
+    }
+
+    #[test]
+    fn test_c_strings() {
+        use super::ffi::*;
+
+        let string = Utf16CString::new("a\u{10000}").unwrap();
+        assert_eq!(string.len(), 3);
+        assert_eq!(string.to_utf16_with_nul(), [0x61, 0xD800, 0xDC00, 0]);
+        let borrowed = unsafe { Utf16CStr::from_ptr(string.as_ptr()) };
+        assert_eq!(borrowed, &*string);
+        assert_eq!(borrowed.as_utf16_str(), "a\u{10000}");
+
+        let error = Utf16CString::new("ab\0c").unwrap_err();
+        assert_eq!(error.nul_position(), 2);
+        assert_eq!(error.into_utf16_string(), "ab\0c");
+
+        assert_eq!(Utf16CStr::from_utf16_with_nul(&[0x61, 0]).unwrap().as_utf16_str(), "a");
+        assert_eq!(Utf16CStr::from_utf16_with_nul(&[0x61]), Err(FromUtf16WithNulError::NotNulTerminated));
+        assert_eq!(Utf16CStr::from_utf16_with_nul(&[0, 0x61, 0]), Err(FromUtf16WithNulError::InteriorNul(0)));
+        let error = Utf16CString::from_vec_with_nul(vec![0x61, 0, 0]).unwrap_err();
+        assert_eq!(error.error(), FromUtf16WithNulError::InteriorNul(1));
+        assert_eq!(error.into_vec(), [0x61, 0, 0]);
+
+        let raw = Utf16CString::new("foo").unwrap().into_raw();
+        let string = unsafe { Utf16CString::from_raw(raw) };
+        assert_eq!(Utf16String::from(string), "foo");
+        assert!(<&Utf16CStr>::default().is_empty());
+    }
+
+    #[test]
+    fn test_cesu8() {
+        use super::cesu8::*;
+
+        let lone = unsafe { Utf16Str::from_utf16_unchecked(&[0xDC00, 0x61, 0xD800]) };
+        let bytes = encode_cesu8(lone);
+        assert_eq!(bytes, b"\xED\xB0\x80a\xED\xA0\x80");
+        assert_eq!(decode_cesu8(&bytes).unwrap(), lone);
+
+        let string = Utf16String::from("\0\u{7F}\u{80}\u{7FF}\u{800}\u{FFFF}\u{10FFFF}");
+        assert_eq!(encode_cesu8(&string).len(), cesu8_len(&string));
+        assert_eq!(encode_modified_utf8(&string).len(), modified_utf8_len(&string));
+        assert_eq!(decode_cesu8(&encode_cesu8(&string)).unwrap(), string);
+        assert_eq!(decode_modified_utf8(&encode_modified_utf8(&string)).unwrap(), string);
+        assert_eq!(&encode_cesu8(&string)[..1], b"\0");
+        assert_eq!(&encode_modified_utf8(&string)[..2], b"\xC0\x80");
+
+        assert_eq!(decode_modified_utf8(b"a\0"), Err(Cesu8Error::InvalidSequence(1)));
+        assert_eq!(decode_cesu8(b"\xC0\x80"), Err(Cesu8Error::InvalidSequence(0)));
+        assert_eq!(decode_cesu8(b"\xF0\x90\x80\x80"), Err(Cesu8Error::InvalidSequence(0)));
+        assert_eq!(decode_cesu8(b"\xE0\x80\x80"), Err(Cesu8Error::InvalidSequence(0)));
+        assert_eq!(decode_cesu8(b"ab\xE4\xB8"), Err(Cesu8Error::UnexpectedEnd(2)));
+
+        let mut out = vec![];
+        write_utf(&Utf16String::from("hi"), &mut out).unwrap();
+        out.push(0xFF);
+        assert_eq!(out, b"\x00\x02hi\xFF");
+        let (string, rest) = read_utf(&out).unwrap();
+        assert_eq!(string, "hi");
+        assert_eq!(rest, [0xFF]);
+        assert_eq!(read_utf(b"\x00\x03hi"), Err(Cesu8Error::UnexpectedEnd(0)));
+        assert_eq!(read_utf(b"\x00\x02h\x80"), Err(Cesu8Error::InvalidSequence(3)));
+
+        let long: Utf16String = std::iter::repeat_n('\u{800}', 21846).collect();
+        assert_eq!(write_utf(&long, &mut out), Err(Cesu8Error::TooLong(65538)));
+        assert_eq!(write_utf(&long[..21845], &mut out), Ok(()));
+    }
+
+    #[test]
+    fn test_wtf8() {
+        use super::wtf8::*;
+
+        let lone = unsafe { Utf16Str::from_utf16_unchecked(&[0xDC00, 0x61, 0xD800, 0xD83E, 0xDD80, 0xDBFF]) };
+        let wtf8 = lone.to_wtf8();
+        assert_eq!(wtf8.as_bytes(), b"\xED\xB0\x80a\xED\xA0\x80\xF0\x9F\xA6\x80\xED\xAF\xBF");
+        assert_eq!(wtf8.to_utf16_string(), lone);
+        assert_eq!(Utf16String::from_wtf8(wtf8.as_bytes()).unwrap(), lone);
+        assert_eq!(wtf8.to_string(), "\u{FFFD}a\u{FFFD}\u{1F980}\u{FFFD}");
+        assert_eq!(wtf8.as_str(), None);
+        assert_eq!(Utf16String::from("a\u{10000}").to_wtf8().as_str(), Some("a\u{10000}"));
+
+        // joining a high and a low surrogate produces a four-byte sequence
+        let mut wtf8 = Wtf8Buf::from(unsafe { Utf16Str::from_utf16_unchecked(&[0xD83E]) });
+        wtf8.push_utf16_str(unsafe { Utf16Str::from_utf16_unchecked(&[0xDD80]) });
+        assert_eq!(wtf8.as_str(), Some("\u{1F980}"));
+
+        assert_eq!(Utf16String::from_wtf8(b"a\xED\xA0\xBD\xED\xB2\xA9").unwrap_err().valid_up_to(), 4);
+        assert_eq!(Utf16String::from_wtf8(b"\xC0\x80").unwrap_err().valid_up_to(), 0);
+        assert_eq!(Utf16String::from_wtf8(b"ab\xF4\x90\x80\x80").unwrap_err().valid_up_to(), 2);
+        assert!(Wtf8Buf::from_bytes(b"\xED\xB0\x80\xED\xA0\x80".to_vec()).is_ok());
+
+        // lone surrogates no longer produce invalid chars
+        assert_eq!(lone.chars().collect::<String>(), "\u{FFFD}a\u{FFFD}\u{1F980}\u{FFFD}");
+        assert_eq!(lone.to_utf8(), "\u{FFFD}a\u{FFFD}\u{1F980}\u{FFFD}");
+        assert_eq!(lone.to_owned().pop(), Some('\u{FFFD}'));
+    }
+
+    #[test]
+    fn test_js_literals() {
+        use super::js::UnescapeError;
+
+        let string = unsafe { Utf16Str::from_utf16_unchecked(&[0x22, 0x5C, 0x00, 0x31, 0x00, 0x0B, 0x1F, 0x7F, 0x2028, 0xD83E, 0xDD80, 0xDC00]) };
+        assert_eq!(string.escape_js(), "\"\\\"\\\\\\x001\\0\\v\\x1f\u{7F}\\u2028\u{1F980}\\udc00\"");
+        assert_eq!(string.escape_json(), "\"\\\"\\\\\\u00001\\u0000\\u000b\\u001f\u{7F}\u{2028}\u{1F980}\\udc00\"");
+        assert_eq!(Utf16String::unescape_js(&string.escape_js().to_utf8()).unwrap(), string);
+        assert_eq!(Utf16String::unescape_js(&string.escape_json().to_utf8()).unwrap(), string);
+
+        assert_eq!(Utf16String::unescape_js("'a\\\r\nb\\\u{2028}c\\q\\''").unwrap(), "abcq'");
+        assert_eq!(Utf16String::unescape_js(r#""\u{D83E}\u{dd80}\u{0000041}""#).unwrap(), "\u{1F980}A");
+        assert_eq!(Utf16String::unescape_js(r"'\08\377\400\8'").unwrap(), "\0\u{38}\u{FF}\u{20}0\u{38}");
+        assert_eq!(Utf16String::unescape_js_strict(r"'\0a'").unwrap(), "\0a");
+        assert_eq!(Utf16String::unescape_js_strict(r"'ab\08'"), Err(UnescapeError::OctalInStrictMode(3)));
+        assert_eq!(Utf16String::unescape_js_strict(r"'\9'"), Err(UnescapeError::OctalInStrictMode(1)));
+
+        assert_eq!(Utf16String::unescape_js("a"), Err(UnescapeError::NotQuoted));
+        assert_eq!(Utf16String::unescape_js("'ab"), Err(UnescapeError::Unterminated));
+        assert_eq!(Utf16String::unescape_js("'ab\\"), Err(UnescapeError::Unterminated));
+        assert_eq!(Utf16String::unescape_js("'é\n'"), Err(UnescapeError::UnexpectedChar(3)));
+        assert_eq!(Utf16String::unescape_js("'a'b"), Err(UnescapeError::UnexpectedChar(3)));
+        assert_eq!(Utf16String::unescape_js(r"'a\x4'"), Err(UnescapeError::InvalidEscape(2)));
+        assert_eq!(Utf16String::unescape_js(r"'\u{110000}'"), Err(UnescapeError::InvalidEscape(1)));
+        assert_eq!(Utf16String::unescape_js(r"'\u{}'"), Err(UnescapeError::InvalidEscape(1)));
+    }
+
+    #[test]
+    fn test_escape_iterators() {
+        for s in ["", "a\tb\n\"'\\", "\u{301}a\u{301}\u{7F}é\u{10FFFF}\u{1F980}", "\0\r"] {
+            let string = Utf16String::from(s);
+            assert_eq!(string.escape_debug().to_string(), s.escape_debug().to_string());
+            assert_eq!(string.escape_default().to_string(), s.escape_default().to_string());
+            assert_eq!(string.escape_unicode().to_string(), s.escape_unicode().to_string());
+            assert!(string.escape_debug().eq(s.escape_debug()));
+        }
+        let lone = unsafe { Utf16Str::from_utf16_unchecked(&[0xDBFF, 0x0A, 0xD83E, 0xDD80, 0xDC0A]) };
+        assert_eq!(lone.escape_debug().to_string(), "\\u{DBFF}\\n\u{1F980}\\u{DC0A}");
+        assert_eq!(lone.escape_default().to_string(), "\\u{DBFF}\\n\\u{1f980}\\u{DC0A}");
+        assert_eq!(lone.escape_unicode().to_string(), "\\u{DBFF}\\u{a}\\u{1f980}\\u{DC0A}");
+    }
+
+    #[test]
+    fn test_parse() {
+        use super::num::string_to_number;
+
+        let parse = |s: &str| Utf16String::from(s);
+        assert_eq!(parse("-128").parse::<i8>(), Ok(-128));
+        assert_eq!(parse("-128").parse::<u8>(), "-128".parse::<u8>());
+        assert_eq!(parse("25é").parse::<u32>(), "25é".parse::<u32>());
+        assert_eq!(parse("").parse::<u32>(), "".parse::<u32>());
+        assert_eq!(parse("1.5e3").parse::<f64>(), Ok(1500.0));
+        assert_eq!(parse("true").parse::<bool>(), Ok(true));
+        let long = "0".repeat(100) + "1";
+        assert_eq!(parse(&long).parse::<u64>(), Ok(1));
+
+        let number = |s: &str| string_to_number(&Utf16String::from(s));
+        assert_eq!(number(""), 0.0);
+        assert_eq!(number(" \u{FEFF}\u{2028}12.5e1\t\n"), 125.0);
+        assert_eq!(number("-0").to_bits(), (-0.0f64).to_bits());
+        assert_eq!(number(".5"), 0.5);
+        assert_eq!(number("5."), 5.0);
+        assert_eq!(number("-Infinity"), f64::NEG_INFINITY);
+        assert_eq!(number("0X1f"), 31.0);
+        assert_eq!(number("0o17"), 15.0);
+        assert_eq!(number("0b101"), 5.0);
+        // 2^53 + 1 rounds to even, 2^53 + 3 rounds up
+        assert_eq!(number("0x20000000000001"), 9007199254740992.0);
+        assert_eq!(number("0x20000000000003"), 9007199254740996.0);
+        assert_eq!(number("0x200000000000000000001"), 2f64.powi(81));
+        // a tie broken by a set bit after it
+        assert_eq!(number("0x1000000000000080000000001"), 2f64.powi(96) + 2f64.powi(44));
+        assert_eq!(number("0x1000000000000080000000000"), 2f64.powi(96));
+        assert_eq!(number(&("0x1".to_string() + &"0".repeat(300))), f64::INFINITY);
+        for s in ["-0x10", "0x", "infinity", "inf", "NaN", "1e", "1_0", "0x1g", "1 2", ".", "+", "١"] {
+            assert!(number(s).is_nan(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_whitespace_and_lines() {
+        use crate::LineTerminators;
+
+        let s = Utf16String::from("\u{FEFF}\u{85} a b\u{3000}\t");
+        assert_eq!(s.trim(), "\u{FEFF}\u{85} a b");
+        assert_eq!(s.trim_js(), "\u{85} a b");
+        assert_eq!(s.trim_start(), "\u{FEFF}\u{85} a b\u{3000}\t");
+        assert_eq!(Utf16String::from("\u{85}a\u{FEFF}").trim_start_js(), "\u{85}a\u{FEFF}");
+        assert_eq!(Utf16String::from(" a ").trim_end_js(), " a");
+        assert_eq!(Utf16String::from("   ").trim(), "");
+
+        let s = Utf16String::from("🦀xa🦀🦀");
+        assert_eq!(s.trim_matches('🦀'), "xa");
+        assert_eq!(s.trim_start_matches(['🦀', 'x']), "a🦀🦀");
+        assert_eq!(s.trim_end_matches(|ch: char| !ch.is_ascii()), "🦀xa");
+        let lone = unsafe { Utf16Str::from_utf16_unchecked(&[0xDC00, 0x61, 0xD800]) };
+        assert_eq!(lone.trim_matches(|_| true), lone);
+
+        let s = Utf16String::from(" a\u{A0}b \u{2003}c\t");
+        assert_eq!(s.split_whitespace().collect::<Vec<_>>(), ["a", "b", "c"]);
+        assert_eq!(s.split_ascii_whitespace().collect::<Vec<_>>(), ["a\u{A0}b", "\u{2003}c"]);
+        assert_eq!(Utf16String::new().split_whitespace().count(), 0);
+
+        let s = Utf16String::from("a\r\nb\rc\nd\u{2028}e\u{2029}\n");
+        assert_eq!(s.lines().collect::<Vec<_>>(), ["a", "b\rc", "d\u{2028}e\u{2029}"]);
+        assert_eq!(s.lines_with_terminators().collect::<Vec<_>>(), ["a\r\n", "b\rc\n", "d\u{2028}e\u{2029}\n"]);
+        let all = LineTerminators::ECMASCRIPT;
+        assert_eq!(s.lines().terminators(all).collect::<Vec<_>>(), ["a", "b", "c", "d", "e", ""]);
+        assert_eq!(s.lines_with_terminators().terminators(LineTerminators::CR | LineTerminators::LF).collect::<Vec<_>>(), ["a\r", "\n", "b\r", "c\n", "d\u{2028}e\u{2029}\n"]);
+        assert!(all.contains(LineTerminators::STD));
+        assert!(!LineTerminators::STD.contains(LineTerminators::CR));
+        assert_eq!(Utf16String::from("x").lines().collect::<Vec<_>>(), ["x"]);
+        assert_eq!(Utf16String::new().lines().count(), 0);
+    }
+
+    #[test]
+    fn test_join() {
+        use crate::{Utf16Join, Utf16Piece};
+
+        let words = [Utf16String::from("a"), Utf16String::from("🦀"), Utf16String::from("")];
+        assert_eq!(words.concat(), "a🦀");
+        assert_eq!(words.join(&Utf16String::from(", ")), "a, 🦀, ");
+        let slices: Vec<&Utf16Str> = words.iter().map(|s| s.as_ref()).collect();
+        assert_eq!(slices[..2].join(&Utf16String::from("-")), "a-🦀");
+        assert_eq!(slices[..0].join(&Utf16String::from("-")), "");
+        let joined = slices.concat();
+        assert_eq!(joined, "a🦀");
+        assert!(joined.buf.capacity() >= joined.len());
+
+        assert_eq!(Utf16String::from("ab").repeat(3), "ababab");
+        assert_eq!(Utf16String::from("ab").repeat(0), "");
+
+        assert_eq!(Utf16Piece::from("é🦀").len(), 3);
+        let name = Utf16String::from("x");
+        let pieces = [Utf16Piece::from("let "), (&name).into(), Utf16Piece::from(" = '🦀'"), ';'.into(), '\u{10000}'.into()];
+        let string: Utf16String = pieces.into_iter().collect();
+        assert_eq!(string, "let x = '🦀';\u{10000}");
+        assert!(string.buf.capacity() >= string.len());
+
+        // extending within the reserved capacity does not reallocate
+        let mut reserved = Utf16String::new();
+        reserved.buf.reserve(string.len());
+        let ptr = reserved.buf.as_ptr();
+        reserved.extend(pieces);
+        assert_eq!(reserved, string);
+        assert_eq!(reserved.buf.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_code_point_order() {
+        use core::cmp::Ordering;
+
+        let supplementary = Utf16String::from("\u{10000}");
+        let private_use = Utf16String::from("\u{E000}");
+        assert_eq!(supplementary.cmp_code_units(&private_use), Ordering::Less);
+        assert_eq!(supplementary.cmp(&private_use), Ordering::Less);
+        assert_eq!(supplementary.cmp_code_points(&private_use), Ordering::Greater);
+        assert_eq!("\u{10000}".cmp("\u{E000}"), Ordering::Greater);
+        assert_eq!(supplementary.cmp_with_str("\u{E000}"), Ordering::Greater);
+        assert_eq!(private_use.cmp_with_str("\u{10000}"), Ordering::Less);
+
+        // the first difference is in the low surrogate
+        let a = Utf16String::from("x\u{10001}");
+        let b = Utf16String::from("x\u{10002}");
+        assert_eq!(a.cmp_code_points(&b), Ordering::Less);
+        assert_eq!(a.cmp_code_points(&a), Ordering::Equal);
+        assert_eq!(a.cmp_with_str("x\u{10001}"), Ordering::Equal);
+        assert_eq!(a.cmp_with_str("x\u{10001}a"), Ordering::Less);
+        assert_eq!(a.cmp_with_str("x"), Ordering::Greater);
+
+        let words = ["b", "\u{FFFD}", "\u{1F980}", "a\u{E000}", "a\u{10000}", "", "a"];
+        let mut utf8 = words.to_vec();
+        utf8.sort();
+        let mut utf16: Vec<Utf16String> = words.iter().map(|w| Utf16String::from(*w)).collect();
+        utf16.sort_by(|a, b| a.cmp_code_points(b));
+        assert_eq!(utf16, utf8);
+
+        let lone = unsafe { Utf16Str::from_utf16_unchecked(&[0xD800]) };
+        assert_eq!(lone.cmp_code_points(&private_use), Ordering::Less);
+        assert_eq!(lone.cmp_code_points(&Utf16String::from("\u{D7FF}")), Ordering::Greater);
+        assert_eq!(lone.cmp_with_str("\u{10000}"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_cross_encoding_hash() {
+        use crate::{utils::{is_high_surrogate, is_low_surrogate}, CodePointHash};
+        use std::hash::{BuildHasher, RandomState};
+
+        let string = Utf16String::from("a🦀b");
+        assert!("a🦀b" == *string);
+        assert!(*"a🦀b" == string);
+        let owned = String::from("a🦀b");
+        assert!(owned == string);
+        assert!("a🦀" != string);
+        assert!(owned == *string);
+
+        let state = RandomState::new();
+        let long = "x🦀".repeat(40);
+        for s in ["", "a", "a🦀b", "\u{FEFF}é", &long] {
+            let utf16 = Utf16String::from(s);
+            assert_eq!(state.hash_one(&utf16), state.hash_one(CodePointHash(s)));
+            assert_eq!(state.hash_one(&*utf16), state.hash_one(CodePointHash(s)));
+            assert_eq!(state.hash_one(crate::SmallUtf16String::from(s)), state.hash_one(CodePointHash(s)));
+        }
+        assert_ne!(state.hash_one(Utf16String::from("ab")), state.hash_one(CodePointHash("ba")));
+
+        // surrogate pairs split across the 32-unit hash blocks
+        let straddling = format!("{}🦀{}🦀b", "a".repeat(31), "c".repeat(30));
+        let utf16 = Utf16String::from(straddling.as_str());
+        assert!(is_high_surrogate(utf16.buf[31]) && is_low_surrogate(utf16.buf[32]));
+        assert!(is_high_surrogate(utf16.buf[63]) && is_low_surrogate(utf16.buf[64]));
+        assert_eq!(state.hash_one(&utf16), state.hash_one(CodePointHash(&straddling)));
+        assert_ne!(state.hash_one(&utf16), state.hash_one(CodePointHash(&straddling[..straddling.len() - 1])));
+
+        #[cfg(feature = "hashbrown")]
+        {
+            let mut map = hashbrown::HashMap::with_hasher(RandomState::new());
+            map.insert(Utf16String::from("length"), 1);
+            map.insert(Utf16String::from(long.as_str()), 2);
+            assert_eq!(map.get(&CodePointHash("length")), Some(&1));
+            assert_eq!(map.get(&CodePointHash(&long)), Some(&2));
+            assert_eq!(map.get(&CodePointHash("size")), None);
+        }
+    }
+
+    #[test]
+    fn test_char_indexing() {
+        use crate::utils::OffsetError;
+
+        let string = Utf16String::from("a🦀é\u{10FFFF}");
+        assert_eq!(string.char_count(), 4);
+        assert_eq!(string.char_count(), string.chars().count());
+        assert_eq!(string.utf8_len(), "a🦀é\u{10FFFF}".len());
+        assert_eq!(Utf16String::new().char_count(), 0);
+        let lone = unsafe { Utf16Str::from_utf16_unchecked(&[0xDC00, 0xD800, 0xD800, 0xDC00, 0xDC00]) };
+        assert_eq!(lone.char_count(), 4);
+        assert_eq!(lone.char_count(), lone.chars().count());
+
+        assert_eq!(string.nth_char(0), Some('a'));
+        assert_eq!(string.nth_char(1), Some('🦀'));
+        assert_eq!(string.nth_char(3), Some('\u{10FFFF}'));
+        assert_eq!(string.nth_char(4), None);
+        assert_eq!(lone.nth_char(2), Some('\u{10000}'));
+        assert_eq!(lone.nth_char(3), Some('\u{FFFD}'));
+
+        let offsets: Vec<_> = (0..=4).map(|n| string.char_offset_to_code_unit(n).unwrap()).collect();
+        assert_eq!(offsets, [0, 1, 3, 4, 6]);
+        assert_eq!(string.char_offset_to_code_unit(5), Err(OffsetError::OutOfBounds(5)));
+        for (n, offset) in offsets.iter().enumerate() {
+            assert_eq!(string.code_unit_to_char_offset(*offset), Ok(n));
+        }
+        assert_eq!(string.code_unit_to_char_offset(2), Err(OffsetError::NotCharBoundary(2)));
+        assert_eq!(string.code_unit_to_char_offset(7), Err(OffsetError::OutOfBounds(7)));
+        assert_eq!(lone.code_unit_to_char_offset(2), Ok(2));
+        assert_eq!(lone.code_unit_to_char_offset(5), Ok(4));
+
+        // a Python-style substring by code points
+        let start = string.char_offset_to_code_unit(1).unwrap();
+        let end = string.char_offset_to_code_unit(3).unwrap();
+        assert_eq!(&string[start..end], "🦀é");
+    }
+
+    #[test]
+    fn test_rope_display() {
+        use crate::rope::Utf16Rope;
+
+        let text = "ab\u{10000}".repeat(400);
+        let rope = Utf16Rope::from(text.as_str());
+        assert!(rope.chunks().count() > 1);
+        assert_eq!(format!("{rope}"), text);
+        assert_eq!(format!("{rope:>1300}"), format!("{text:>1300}"));
+        assert_eq!(format!("{rope:-^1250}"), format!("{text:-^1250}"));
+        assert_eq!(format!("{rope:.3}"), "ab\u{10000}");
+        assert_eq!(format!("{rope:>5.2}"), "   ab");
+    }
 }
\ No newline at end of file
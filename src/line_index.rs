@@ -0,0 +1,252 @@
+//! Line and column index for fast conversion between offsets and positions.
+//!
+//! A [`LineIndex`] is built once over a text and then answers offset and
+//! position queries in O(log n), as opposed to the functions in
+//! [`crate::utils`], which rescan the string on every call.
+//!
+//! ```
+//! use hydroperx_utf16::{Utf16String, line_index::*};
+//!
+//! let text = Utf16String::from("a\n\u{10000}b");
+//! let index = LineIndex::new(&text);
+//!
+//! // 'b' is at UTF-16 offset 4 and UTF-8 offset 6
+//! assert_eq!(index.convert_offset(4, OffsetUnit::Utf16, OffsetUnit::Utf8), Some(6));
+//! assert_eq!(index.position(4, OffsetUnit::Utf16, OffsetUnit::Utf16), Some(LineColumn { line: 1, column: 2 }));
+//! assert_eq!(index.position(4, OffsetUnit::Utf16, OffsetUnit::Char), Some(LineColumn { line: 1, column: 1 }));
+//! ```
+
+use crate::{utils::*, Utf16Str};
+use alloc::{vec, vec::Vec};
+
+/// The unit in which an offset or a column is expressed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum OffsetUnit {
+    /// UTF-16 code units.
+    Utf16,
+    /// UTF-8 bytes.
+    Utf8,
+    /// Code points.
+    Char,
+}
+
+/// A zero-based line and column pair.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A location in the text expressed in every unit.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+struct Point {
+    utf16: usize,
+    utf8: usize,
+    chars: usize,
+}
+
+impl Point {
+    #[inline]
+    fn get(&self, unit: OffsetUnit) -> usize {
+        match unit {
+            OffsetUnit::Utf16 => self.utf16,
+            OffsetUnit::Utf8 => self.utf8,
+            OffsetUnit::Char => self.chars,
+        }
+    }
+
+    #[inline]
+    fn advance(&mut self, utf16_len: usize, utf8_len: usize) {
+        self.utf16 += utf16_len;
+        self.utf8 += utf8_len;
+        self.chars += 1;
+    }
+}
+
+/// A code point that does not occupy exactly one unit in every encoding.
+#[derive(Copy, Clone, Debug)]
+struct WideChar {
+    start: Point,
+    utf16_len: u8,
+    utf8_len: u8,
+}
+
+impl WideChar {
+    #[inline]
+    fn end(&self) -> Point {
+        let mut end = self.start;
+        end.advance(self.utf16_len as usize, self.utf8_len as usize);
+        end
+    }
+}
+
+/// Index of line starts and non-ASCII code points in a text.
+///
+/// Lines are terminated by `\n`, `\r\n` or `\r`, as in the Language Server
+/// Protocol. A lone surrogate is treated as if it were U+FFFD, occupying
+/// one UTF-16 code unit and three UTF-8 bytes.
+#[derive(Clone, Debug)]
+pub struct LineIndex {
+    /// Start of every line.
+    lines: Vec<Point>,
+    /// Every code point that is not ASCII, in order.
+    wide: Vec<WideChar>,
+    /// End of the text.
+    len: Point,
+}
+
+impl LineIndex {
+    /// Builds an index over a UTF-16 string.
+    pub fn new(text: &Utf16Str) -> Self {
+        let mut builder = LineIndexBuilder::new();
+        let raw = &text.raw;
+        let mut i = 0;
+        while i < raw.len() {
+            let cu1 = raw[i];
+            if is_high_surrogate(cu1) && (i + 1) < raw.len() && is_low_surrogate(raw[i + 1]) {
+                builder.push(decode_char(cu1, raw[i + 1]), raw.get(i + 2) == Some(&(b'\n' as u16)));
+                i += 2;
+            } else {
+                let ch = code_unit_to_char(cu1);
+                builder.push(ch, raw.get(i + 1) == Some(&(b'\n' as u16)));
+                i += 1;
+            }
+        }
+        builder.finish()
+    }
+
+    /// Builds an index over a UTF-8 string.
+    pub fn from_utf8(text: &str) -> Self {
+        let mut builder = LineIndexBuilder::new();
+        let mut chars = text.chars().peekable();
+        while let Some(ch) = chars.next() {
+            builder.push(ch, chars.peek() == Some(&'\n'));
+        }
+        builder.finish()
+    }
+
+    /// Returns the number of lines. An empty text has one line.
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns the length of the text in the given unit.
+    #[inline]
+    pub fn len(&self, unit: OffsetUnit) -> usize {
+        self.len.get(unit)
+    }
+
+    /// Returns whether the indexed text is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len.utf16 == 0
+    }
+
+    /// Returns the offset at which a zero-based line starts, or `None`
+    /// if the line does not exist.
+    pub fn line_start(&self, line: usize, unit: OffsetUnit) -> Option<usize> {
+        self.lines.get(line).map(|point| point.get(unit))
+    }
+
+    /// Converts an offset between units.
+    ///
+    /// Returns `None` if the offset is out of bounds or does not fall on a
+    /// code point boundary.
+    pub fn convert_offset(&self, offset: usize, from: OffsetUnit, to: OffsetUnit) -> Option<usize> {
+        self.point(offset, from).map(|point| point.get(to))
+    }
+
+    /// Converts an offset into a zero-based line and column pair, with the
+    /// column expressed in `column_unit`.
+    ///
+    /// Returns `None` if the offset is out of bounds or does not fall on a
+    /// code point boundary.
+    pub fn position(&self, offset: usize, offset_unit: OffsetUnit, column_unit: OffsetUnit) -> Option<LineColumn> {
+        let point = self.point(offset, offset_unit)?;
+        let line = self.lines.partition_point(|start| start.utf16 <= point.utf16) - 1;
+        let column = point.get(column_unit) - self.lines[line].get(column_unit);
+        Some(LineColumn { line, column })
+    }
+
+    /// Converts a zero-based line and column pair, with the column
+    /// expressed in `column_unit`, into an offset in `offset_unit`.
+    ///
+    /// Returns `None` if the line does not exist, if the column is past the
+    /// end of the line (including its terminator) or if the column does not
+    /// fall on a code point boundary.
+    pub fn offset(&self, position: LineColumn, column_unit: OffsetUnit, offset_unit: OffsetUnit) -> Option<usize> {
+        let start = self.lines.get(position.line)?.get(column_unit);
+        let end = self.lines.get(position.line + 1).unwrap_or(&self.len).get(column_unit);
+        let offset = start.checked_add(position.column)?;
+        if offset > end {
+            return None;
+        }
+        self.convert_offset(offset, column_unit, offset_unit)
+    }
+
+    fn point(&self, offset: usize, unit: OffsetUnit) -> Option<Point> {
+        if offset > self.len.get(unit) {
+            return None;
+        }
+        let i = self.wide.partition_point(|ch| ch.start.get(unit) <= offset);
+        if i == 0 {
+            return Some(Point { utf16: offset, utf8: offset, chars: offset });
+        }
+        let ch = &self.wide[i - 1];
+        if offset == ch.start.get(unit) {
+            return Some(ch.start);
+        }
+        let end = ch.end();
+        if offset < end.get(unit) {
+            return None;
+        }
+        let delta = offset - end.get(unit);
+        Some(Point {
+            utf16: end.utf16 + delta,
+            utf8: end.utf8 + delta,
+            chars: end.chars + delta,
+        })
+    }
+}
+
+struct LineIndexBuilder {
+    lines: Vec<Point>,
+    wide: Vec<WideChar>,
+    point: Point,
+}
+
+impl LineIndexBuilder {
+    fn new() -> Self {
+        Self {
+            lines: vec![Point::default()],
+            wide: vec![],
+            point: Point::default(),
+        }
+    }
+
+    /// Pushes a code point, given whether it is followed by a line feed.
+    fn push(&mut self, ch: char, followed_by_lf: bool) {
+        let utf16_len = ch.len_utf16();
+        let utf8_len = ch.len_utf8();
+        if utf8_len != 1 {
+            self.wide.push(WideChar {
+                start: self.point,
+                utf16_len: utf16_len as u8,
+                utf8_len: utf8_len as u8,
+            });
+        }
+        self.point.advance(utf16_len, utf8_len);
+        if ch == '\n' || (ch == '\r' && !followed_by_lf) {
+            self.lines.push(self.point);
+        }
+    }
+
+    fn finish(self) -> LineIndex {
+        LineIndex {
+            lines: self.lines,
+            wide: self.wide,
+            len: self.point,
+        }
+    }
+}
\ No newline at end of file
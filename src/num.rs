@@ -0,0 +1,159 @@
+//! Numeric parsing without converting to UTF-8.
+//!
+//! ```
+//! use hydroperx_utf16::{Utf16String, num::string_to_number};
+//!
+//! let string = Utf16String::from("-42");
+//! assert_eq!(string.parse::<i32>(), Ok(-42));
+//! assert_eq!(string_to_number(&Utf16String::from(" 0x1F\n")), 31.0);
+//! assert!(string_to_number(&Utf16String::from("1_000")).is_nan());
+//! ```
+
+use core::convert::Infallible;
+use core::num::{ParseFloatError, ParseIntError};
+use core::str::{FromStr, ParseBoolError};
+use alloc::borrow::ToOwned;
+use alloc::vec;
+
+use crate::{utils::*, Utf16Str, Utf16String};
+
+/// Parses a value from a [`Utf16Str`], as `FromStr` does from a `str`.
+pub trait FromUtf16Str: Sized {
+    type Err;
+
+    fn from_utf16_str(s: &Utf16Str) -> Result<Self, Self::Err>;
+}
+
+impl Utf16Str {
+    /// Parses the string into another type, as `str::parse` does.
+    #[inline]
+    pub fn parse<F: FromUtf16Str>(&self) -> Result<F, F::Err> {
+        F::from_utf16_str(self)
+    }
+}
+
+/// Strings no longer than this are parsed without allocating.
+const STACK_LEN: usize = 64;
+
+/// Calls `f` with the string narrowed to ASCII. Other code units become
+/// `x`, which no numeric or boolean syntax accepts.
+fn with_ascii<R>(s: &Utf16Str, f: impl FnOnce(&str) -> R) -> R {
+    let mut stack = [0u8; STACK_LEN];
+    let mut heap = vec![];
+    let bytes = if s.len() <= STACK_LEN {
+        &mut stack[..s.len()]
+    } else {
+        heap.resize(s.len(), 0);
+        &mut heap[..]
+    };
+    for (b, cu) in bytes.iter_mut().zip(&s.raw) {
+        *b = if *cu < 0x80 { *cu as u8 } else { b'x' };
+    }
+    // the bytes are ASCII
+    f(unsafe { core::str::from_utf8_unchecked(bytes) })
+}
+
+macro_rules! from_utf16_str_via_ascii {
+    ($err:ty => $($t:ty)*) => {
+        $(
+            impl FromUtf16Str for $t {
+                type Err = $err;
+
+                #[inline]
+                fn from_utf16_str(s: &Utf16Str) -> Result<Self, $err> {
+                    with_ascii(s, <$t>::from_str)
+                }
+            }
+        )*
+    };
+}
+
+from_utf16_str_via_ascii!(ParseIntError => i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+from_utf16_str_via_ascii!(ParseFloatError => f32 f64);
+from_utf16_str_via_ascii!(ParseBoolError => bool);
+
+impl FromUtf16Str for Utf16String {
+    type Err = Infallible;
+
+    #[inline]
+    fn from_utf16_str(s: &Utf16Str) -> Result<Self, Infallible> {
+        Ok(s.to_owned())
+    }
+}
+
+/// Converts a string to a number with the semantics of JavaScript's
+/// `Number(string)`, returning NaN if the string is not numeric.
+///
+/// Surrounding whitespace and line terminators are ignored, an empty
+/// string is zero, and `0x`, `0o` and `0b` prefixes, `Infinity` and signed
+/// decimal literals are accepted.
+pub fn string_to_number(s: &Utf16Str) -> f64 {
+    let mut raw = &s.raw;
+    while let [cu, rest @ ..] = raw
+        && is_js_whitespace(*cu)
+    {
+        raw = rest;
+    }
+    while let [rest @ .., cu] = raw
+        && is_js_whitespace(*cu)
+    {
+        raw = rest;
+    }
+    if raw.is_empty() {
+        return 0.0;
+    }
+    let raw = unsafe { Utf16Str::from_utf16_unchecked(raw) };
+    match raw.raw {
+        [0x30, 0x78 | 0x58, ref digits @ ..] => non_decimal(digits, 4),
+        [0x30, 0x6F | 0x4F, ref digits @ ..] => non_decimal(digits, 3),
+        [0x30, 0x62 | 0x42, ref digits @ ..] => non_decimal(digits, 1),
+        _ => with_ascii(raw, decimal),
+    }
+}
+
+/// Parses digits of a power-of-two radix, rounding to nearest even.
+fn non_decimal(digits: &[u16], bits: u32) -> f64 {
+    if digits.is_empty() {
+        return f64::NAN;
+    }
+    // leading bits of the value, the count of bits dropped after them and
+    // whether any dropped bit is set
+    let mut mantissa = 0u64;
+    let mut exp = 0i32;
+    let mut sticky = false;
+    for &cu in digits {
+        let Some(digit) = char::from_u32(cu as u32).and_then(|ch| ch.to_digit(1 << bits)) else {
+            return f64::NAN;
+        };
+        for i in (0..bits).rev() {
+            let bit = (digit >> i) & 1;
+            if mantissa >> 63 == 0 {
+                mantissa = mantissa << 1 | bit as u64;
+            } else {
+                exp = exp.saturating_add(1);
+                sticky |= bit == 1;
+            }
+        }
+    }
+    // with 64 significant bits, setting the lowest bit for dropped bits
+    // only breaks ties when converting to 53 bits
+    if sticky {
+        mantissa |= 1;
+    }
+    // 2^exp, built from its bits as `powi` needs `std`
+    let scale = if exp > 1023 { f64::INFINITY } else { f64::from_bits(((exp + 1023) as u64) << 52) };
+    mantissa as f64 * scale
+}
+
+/// Parses a `StrDecimalLiteral`.
+fn decimal(s: &str) -> f64 {
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+    if unsigned == "Infinity" {
+        return if s.starts_with('-') { f64::NEG_INFINITY } else { f64::INFINITY };
+    }
+    // `f64::from_str` also accepts `inf` and `nan`, which JavaScript does not
+    if !unsigned.bytes().all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'e' | b'E' | b'+' | b'-')) {
+        return f64::NAN;
+    }
+    s.parse().unwrap_or(f64::NAN)
+}
\ No newline at end of file
@@ -0,0 +1,159 @@
+//! Offset and range types that carry their encoding unit.
+//!
+//! Offsets of different encodings cannot be mixed up by accident, and
+//! only UTF-16 offsets can be used for slicing a [`Utf16Str`]:
+//!
+//! ```
+//! use hydroperx_utf16::{Utf16String, offset::*};
+//!
+//! let utf8string = "a\u{10FFFF}b";
+//! let utf16string = Utf16String::from(utf8string);
+//!
+//! let start = Utf8Offset(5).to_utf16(utf8string).unwrap();
+//! assert_eq!(start, Utf16Offset(3));
+//! assert_eq!(utf16string[start..].to_utf8(), "b");
+//! ```
+
+use core::ops::Range;
+
+use crate::{utils::*, Utf16Str};
+
+/// An offset in UTF-16 code units.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Utf16Offset(pub usize);
+
+/// An offset in UTF-8 bytes.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Utf8Offset(pub usize);
+
+/// An offset in code points.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CharOffset(pub usize);
+
+/// A range of UTF-16 code units.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Utf16Range {
+    pub start: Utf16Offset,
+    pub end: Utf16Offset,
+}
+
+impl Utf16Offset {
+    /// Converts into a UTF-8 offset, given the UTF-16 string the offset
+    /// refers to.
+    pub fn to_utf8(self, utf16string: &Utf16Str) -> Result<Utf8Offset, OffsetError> {
+        utf16string.utf8_offset_of(self.0).map(Utf8Offset)
+    }
+
+    /// Converts into a code point offset, given the UTF-16 string the
+    /// offset refers to.
+    pub fn to_char(self, utf16string: &Utf16Str) -> Result<CharOffset, OffsetError> {
+        utf16string.code_unit_to_char_offset(self.0).map(CharOffset)
+    }
+}
+
+impl Utf8Offset {
+    /// Converts into a UTF-16 offset, given the UTF-8 string the offset
+    /// refers to.
+    pub fn to_utf16(self, utf8string: &str) -> Result<Utf16Offset, OffsetError> {
+        utf8string.utf16_offset_of(self.0).map(Utf16Offset)
+    }
+
+    /// Converts into a code point offset, given the UTF-8 string the
+    /// offset refers to.
+    pub fn to_char(self, utf8string: &str) -> Result<CharOffset, OffsetError> {
+        if self.0 > utf8string.len() {
+            return Err(OffsetError::OutOfBounds(self.0));
+        }
+        if !utf8string.is_char_boundary(self.0) {
+            return Err(OffsetError::NotCharBoundary(self.0));
+        }
+        Ok(CharOffset(utf8string[..self.0].chars().count()))
+    }
+}
+
+impl CharOffset {
+    /// Converts into a UTF-16 offset, given the UTF-16 string the offset
+    /// refers to.
+    pub fn to_utf16(self, utf16string: &Utf16Str) -> Result<Utf16Offset, OffsetError> {
+        utf16string.char_offset_to_code_unit(self.0).map(Utf16Offset)
+    }
+
+    /// Converts into a UTF-8 offset, given the UTF-8 string the offset
+    /// refers to.
+    pub fn to_utf8(self, utf8string: &str) -> Result<Utf8Offset, OffsetError> {
+        utf8string.char_indices()
+            .map(|(i, _)| i)
+            .chain(core::iter::once(utf8string.len()))
+            .nth(self.0)
+            .map(Utf8Offset)
+            .ok_or(OffsetError::OutOfBounds(self.0))
+    }
+}
+
+impl Utf16Range {
+    #[inline]
+    pub fn new(start: Utf16Offset, end: Utf16Offset) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the length of the range in code units.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.end.0.saturating_sub(self.start.0)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.end.0 <= self.start.0
+    }
+
+    /// Converts into a UTF-8 range, given the UTF-16 string the range
+    /// refers to.
+    pub fn to_utf8(self, utf16string: &Utf16Str) -> Result<Range<Utf8Offset>, OffsetError> {
+        let range = utf16string.utf8_range_of(self.start.0..self.end.0)?;
+        Ok(Utf8Offset(range.start)..Utf8Offset(range.end))
+    }
+
+    /// Converts a UTF-8 range into a UTF-16 range, given the UTF-8 string
+    /// the range refers to.
+    pub fn from_utf8(utf8string: &str, utf8range: Range<Utf8Offset>) -> Result<Self, OffsetError> {
+        let range = utf8string.utf16_range_of(utf8range.start.0..utf8range.end.0)?;
+        Ok(Self::new(Utf16Offset(range.start), Utf16Offset(range.end)))
+    }
+}
+
+impl From<Range<Utf16Offset>> for Utf16Range {
+    fn from(value: Range<Utf16Offset>) -> Self {
+        Self::new(value.start, value.end)
+    }
+}
+
+impl From<Utf16Range> for Range<Utf16Offset> {
+    fn from(value: Utf16Range) -> Self {
+        value.start..value.end
+    }
+}
+
+impl From<Utf16Range> for Range<usize> {
+    fn from(value: Utf16Range) -> Self {
+        value.start.0..value.end.0
+    }
+}
+
+impl From<Utf16Offset> for usize {
+    fn from(value: Utf16Offset) -> Self {
+        value.0
+    }
+}
+
+impl From<Utf8Offset> for usize {
+    fn from(value: Utf8Offset) -> Self {
+        value.0
+    }
+}
+
+impl From<CharOffset> for usize {
+    fn from(value: CharOffset) -> Self {
+        value.0
+    }
+}
\ No newline at end of file
@@ -0,0 +1,629 @@
+//! Rope for large editable UTF-16 text.
+//!
+//! A [`Utf16Rope`] is a balanced tree of [`Utf16Str`] chunks. Inserting
+//! and removing at code unit offsets takes O(log n), and cloning shares
+//! the whole tree, so snapshots are cheap. Requires pointer-sized atomics,
+//! since snapshots share the tree through `Arc`.
+//!
+//! ```
+//! use hydroperx_utf16::{rope::Utf16Rope, line_index::OffsetUnit};
+//!
+//! let mut rope = Utf16Rope::from("hello\nworld");
+//! let snapshot = rope.clone();
+//! rope.insert_utf8_str(5, ", \u{10000}");
+//! rope.remove(0..1);
+//!
+//! assert_eq!(rope.to_string(), "ello, \u{10000}\nworld");
+//! assert_eq!(snapshot.to_string(), "hello\nworld");
+//! assert_eq!(rope.line_count(), 2);
+//! assert_eq!(rope.convert_offset(8, OffsetUnit::Utf16, OffsetUnit::Utf8), Some(10));
+//! ```
+
+use core::fmt::{self, Write};
+use core::ops::Range;
+use alloc::sync::Arc;
+use alloc::{string::String, vec, vec::Vec};
+
+use crate::{line_index::OffsetUnit, utils::*, Utf16Str, Utf16String};
+
+/// Maximum number of code units in a chunk.
+const MAX_CHUNK: usize = 512;
+
+/// Text metrics of a chunk or a subtree.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+struct Metrics {
+    utf16: usize,
+    utf8: usize,
+    chars: usize,
+    /// Line breaks: `\n`, `\r\n` and a lone `\r`.
+    lines: usize,
+}
+
+impl Metrics {
+    #[inline]
+    fn get(&self, unit: OffsetUnit) -> usize {
+        match unit {
+            OffsetUnit::Utf16 => self.utf16,
+            OffsetUnit::Utf8 => self.utf8,
+            OffsetUnit::Char => self.chars,
+        }
+    }
+
+    #[inline]
+    fn add(self, other: Metrics) -> Metrics {
+        Metrics {
+            utf16: self.utf16 + other.utf16,
+            utf8: self.utf8 + other.utf8,
+            chars: self.chars + other.chars,
+            lines: self.lines + other.lines,
+        }
+    }
+
+    /// Measures `chunk[..end]`. A `\r` before `end` counts as a line break
+    /// unless `\n` follows it in the chunk.
+    fn of_prefix(chunk: &[u16], end: usize) -> Metrics {
+        let mut metrics = Metrics {
+            utf16: end,
+            utf8: utf8_len_of_code_units(&chunk[..end]),
+            chars: 0,
+            lines: 0,
+        };
+        for i in 0..end {
+            let cu = chunk[i];
+            if !(i > 0 && is_low_surrogate(cu) && is_high_surrogate(chunk[i - 1])) {
+                metrics.chars += 1;
+            }
+            if cu == b'\n' as u16 || (cu == b'\r' as u16 && chunk.get(i + 1) != Some(&(b'\n' as u16))) {
+                metrics.lines += 1;
+            }
+        }
+        metrics
+    }
+}
+
+/// Returns whether two adjacent code units must stay in the same chunk.
+#[inline]
+fn is_seam(left: u16, right: u16) -> bool {
+    (is_high_surrogate(left) && is_low_surrogate(right)) || (left == b'\r' as u16 && right == b'\n' as u16)
+}
+
+#[derive(Clone)]
+enum Node {
+    Leaf {
+        chunk: Utf16String,
+        metrics: Metrics,
+    },
+    Branch {
+        left: Arc<Node>,
+        right: Arc<Node>,
+        height: u8,
+        metrics: Metrics,
+    },
+}
+
+impl Node {
+    fn leaf(chunk: &[u16]) -> Arc<Node> {
+        Arc::new(Node::Leaf {
+            chunk: Utf16String { buf: chunk.to_vec() },
+            metrics: Metrics::of_prefix(chunk, chunk.len()),
+        })
+    }
+
+    fn branch(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+        Arc::new(Node::Branch {
+            height: left.height().max(right.height()) + 1,
+            metrics: left.metrics().add(right.metrics()),
+            left,
+            right,
+        })
+    }
+
+    #[inline]
+    fn metrics(&self) -> Metrics {
+        match self {
+            Node::Leaf { metrics, .. } | Node::Branch { metrics, .. } => *metrics,
+        }
+    }
+
+    #[inline]
+    fn height(&self) -> u8 {
+        match self {
+            Node::Leaf { .. } => 0,
+            Node::Branch { height, .. } => *height,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.metrics().utf16
+    }
+
+    fn children(&self) -> (&Arc<Node>, &Arc<Node>) {
+        match self {
+            Node::Branch { left, right, .. } => (left, right),
+            Node::Leaf { .. } => unreachable!(),
+        }
+    }
+
+    fn first_code_unit(&self) -> Option<u16> {
+        match self {
+            Node::Leaf { chunk, .. } => chunk.buf.first().copied(),
+            Node::Branch { left, .. } => left.first_code_unit(),
+        }
+    }
+
+    fn last_code_unit(&self) -> Option<u16> {
+        match self {
+            Node::Leaf { chunk, .. } => chunk.buf.last().copied(),
+            Node::Branch { right, .. } => right.last_code_unit(),
+        }
+    }
+
+    /// Builds a balanced tree out of code units.
+    fn build(raw: &[u16]) -> Arc<Node> {
+        let mut leaves = vec![];
+        let mut start = 0;
+        while start < raw.len() {
+            let mut end = (start + MAX_CHUNK).min(raw.len());
+            if end < raw.len() && is_seam(raw[end - 1], raw[end]) {
+                end -= 1;
+            }
+            leaves.push(Node::leaf(&raw[start..end]));
+            start = end;
+        }
+        if leaves.is_empty() {
+            return Node::leaf(&[]);
+        }
+        Node::build_from_leaves(&leaves)
+    }
+
+    fn build_from_leaves(leaves: &[Arc<Node>]) -> Arc<Node> {
+        if leaves.len() == 1 {
+            return leaves[0].clone();
+        }
+        let mid = leaves.len() / 2;
+        Node::branch(Node::build_from_leaves(&leaves[..mid]), Node::build_from_leaves(&leaves[mid..]))
+    }
+
+    /// Restores balance of two subtrees whose heights differ by at most two.
+    fn balance(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+        let (hl, hr) = (left.height(), right.height());
+        if hl > hr + 1 {
+            let (ll, lr) = left.children();
+            if ll.height() >= lr.height() {
+                Node::branch(ll.clone(), Node::branch(lr.clone(), right))
+            } else {
+                let (lrl, lrr) = lr.children();
+                Node::branch(Node::branch(ll.clone(), lrl.clone()), Node::branch(lrr.clone(), right))
+            }
+        } else if hr > hl + 1 {
+            let (rl, rr) = right.children();
+            if rr.height() >= rl.height() {
+                Node::branch(Node::branch(left, rl.clone()), rr.clone())
+            } else {
+                let (rll, rlr) = rl.children();
+                Node::branch(Node::branch(left, rll.clone()), Node::branch(rlr.clone(), rr.clone()))
+            }
+        } else {
+            Node::branch(left, right)
+        }
+    }
+
+    /// Concatenates two balanced trees into a balanced tree.
+    fn join(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+        if left.len() == 0 {
+            return right;
+        }
+        if right.len() == 0 {
+            return left;
+        }
+        if let (Node::Leaf { chunk: a, .. }, Node::Leaf { chunk: b, .. }) = (&*left, &*right)
+            && a.len() + b.len() <= MAX_CHUNK
+        {
+            return Node::leaf(&[&a.buf[..], &b.buf[..]].concat());
+        }
+        let (hl, hr) = (left.height(), right.height());
+        if hl > hr + 1 {
+            let (ll, lr) = left.children();
+            Node::balance(ll.clone(), Node::join(lr.clone(), right))
+        } else if hr > hl + 1 {
+            let (rl, rr) = right.children();
+            Node::balance(Node::join(left, rl.clone()), rr.clone())
+        } else {
+            Node::branch(left, right)
+        }
+    }
+
+    /// Concatenates two trees, keeping a surrogate pair or `\r\n` that
+    /// spans them within one chunk.
+    fn concat(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+        match (left.last_code_unit(), right.first_code_unit()) {
+            (Some(a), Some(b)) if is_seam(a, b) => {
+                let (left, _) = Node::split(&left, left.len() - 1);
+                let (_, right) = Node::split(&right, 1);
+                Node::join(Node::join(left, Node::leaf(&[a, b])), right)
+            },
+            _ => Node::join(left, right),
+        }
+    }
+
+    /// Splits a tree at a code unit offset.
+    fn split(node: &Arc<Node>, offset: usize) -> (Arc<Node>, Arc<Node>) {
+        match &**node {
+            Node::Leaf { chunk, .. } => {
+                if offset == 0 {
+                    (Node::leaf(&[]), node.clone())
+                } else if offset == chunk.len() {
+                    (node.clone(), Node::leaf(&[]))
+                } else {
+                    (Node::leaf(&chunk.buf[..offset]), Node::leaf(&chunk.buf[offset..]))
+                }
+            },
+            Node::Branch { left, right, .. } => {
+                let left_len = left.len();
+                if offset < left_len {
+                    let (a, b) = Node::split(left, offset);
+                    (a, Node::join(b, right.clone()))
+                } else if offset == left_len {
+                    (left.clone(), right.clone())
+                } else {
+                    let (a, b) = Node::split(right, offset - left_len);
+                    (Node::join(left.clone(), a), b)
+                }
+            },
+        }
+    }
+
+    /// Inserts into the chunk containing the offset, if it has room.
+    fn insert_in_place(node: &mut Arc<Node>, offset: usize, string: &Utf16Str) -> bool {
+        match Arc::make_mut(node) {
+            Node::Leaf { chunk, metrics } => {
+                if chunk.len() + string.len() > MAX_CHUNK {
+                    return false;
+                }
+                chunk.insert_utf16_str(offset, string);
+                *metrics = Metrics::of_prefix(&chunk.buf, chunk.len());
+                true
+            },
+            Node::Branch { left, right, metrics, .. } => {
+                let left_len = left.len();
+                let inserted = if offset <= left_len {
+                    Node::insert_in_place(left, offset, string)
+                } else {
+                    Node::insert_in_place(right, offset - left_len, string)
+                };
+                if inserted {
+                    *metrics = left.metrics().add(right.metrics());
+                }
+                inserted
+            },
+        }
+    }
+
+    /// Returns the metrics of the text before an offset in the given unit,
+    /// or `None` if the offset does not fall on a code point boundary.
+    fn seek(&self, offset: usize, unit: OffsetUnit) -> Option<Metrics> {
+        match self {
+            Node::Leaf { chunk, .. } => {
+                let raw = &chunk.buf;
+                let end = match unit {
+                    OffsetUnit::Utf16 => offset,
+                    OffsetUnit::Utf8 | OffsetUnit::Char => {
+                        let mut position = 0;
+                        let mut i = 0;
+                        while position < offset && i < raw.len() {
+                            let pair = is_high_surrogate(raw[i]) && raw.get(i + 1).is_some_and(|cu| is_low_surrogate(*cu));
+                            let len = if pair { 2 } else { 1 };
+                            position += match unit {
+                                OffsetUnit::Char => 1,
+                                _ => utf8_len_of_code_units(&raw[i..i + len]),
+                            };
+                            i += len;
+                        }
+                        if position != offset {
+                            return None;
+                        }
+                        i
+                    },
+                };
+                if end > raw.len() || (end > 0 && end < raw.len() && is_high_surrogate(raw[end - 1]) && is_low_surrogate(raw[end])) {
+                    return None;
+                }
+                Some(Metrics::of_prefix(raw, end))
+            },
+            Node::Branch { left, right, .. } => {
+                let left_metrics = left.metrics();
+                if offset < left_metrics.get(unit) {
+                    left.seek(offset, unit)
+                } else {
+                    right.seek(offset - left_metrics.get(unit), unit).map(|m| left_metrics.add(m))
+                }
+            },
+        }
+    }
+
+    /// Returns the metrics of the text before the start of a line, given
+    /// `line > 0` and `line <= self.metrics().lines`.
+    fn seek_line(&self, line: usize) -> Metrics {
+        match self {
+            Node::Leaf { chunk, .. } => {
+                let raw = &chunk.buf;
+                let mut lines = 0;
+                for i in 0..raw.len() {
+                    let cu = raw[i];
+                    if cu == b'\n' as u16 || (cu == b'\r' as u16 && raw.get(i + 1) != Some(&(b'\n' as u16))) {
+                        lines += 1;
+                        if lines == line {
+                            return Metrics::of_prefix(raw, i + 1);
+                        }
+                    }
+                }
+                unreachable!()
+            },
+            Node::Branch { left, right, .. } => {
+                let left_metrics = left.metrics();
+                if line <= left_metrics.lines {
+                    left.seek_line(line)
+                } else {
+                    left_metrics.add(right.seek_line(line - left_metrics.lines))
+                }
+            },
+        }
+    }
+}
+
+/// A balanced tree of UTF-16 chunks supporting O(log n) edits.
+///
+/// Chunk boundaries never split a surrogate pair or a `\r\n` sequence.
+/// Lines are terminated by `\n`, `\r\n` or `\r`, and a lone surrogate is
+/// measured as if it were U+FFFD, as in [`crate::line_index::LineIndex`].
+#[derive(Clone)]
+pub struct Utf16Rope {
+    root: Arc<Node>,
+}
+
+impl Utf16Rope {
+    pub fn new() -> Self {
+        Utf16Rope {
+            root: Node::leaf(&[]),
+        }
+    }
+
+    /// Returns the number of UTF-16 code units.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.root.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of UTF-8 bytes needed to encode the text.
+    #[inline]
+    pub fn utf8_len(&self) -> usize {
+        self.root.metrics().utf8
+    }
+
+    /// Returns the number of code points.
+    #[inline]
+    pub fn char_count(&self) -> usize {
+        self.root.metrics().chars
+    }
+
+    /// Returns the number of lines. An empty rope has one line.
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.root.metrics().lines + 1
+    }
+
+    /// Inserts a string at a code unit offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is out of bounds.
+    pub fn insert(&mut self, offset: usize, string: &Utf16Str) {
+        assert!(offset <= self.len(), "inserting out of bounds of Utf16Rope");
+        if string.is_empty() {
+            return;
+        }
+        let first = string.raw[0];
+        let last = string.raw[string.len() - 1];
+        // Edits that cannot create a seam between chunks stay in one chunk.
+        let safe = !is_low_surrogate(first) && first != b'\n' as u16 && !is_high_surrogate(last) && last != b'\r' as u16;
+        if safe && Node::insert_in_place(&mut self.root, offset, string) {
+            return;
+        }
+        let (left, right) = Node::split(&self.root, offset);
+        self.root = Node::concat(Node::concat(left, Node::build(&string.raw)), right);
+    }
+
+    /// Inserts a UTF-8 string at a code unit offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is out of bounds.
+    pub fn insert_utf8_str(&mut self, offset: usize, string: &str) {
+        self.insert(offset, &Utf16String::from(string));
+    }
+
+    /// Removes a range of code units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn remove(&mut self, range: Range<usize>) {
+        assert!(range.start <= range.end && range.end <= self.len(), "removing out of bounds of Utf16Rope");
+        if range.is_empty() {
+            return;
+        }
+        let (left, rest) = Node::split(&self.root, range.start);
+        let (_, right) = Node::split(&rest, range.end - range.start);
+        self.root = Node::concat(left, right);
+    }
+
+    /// Returns a rope of a range of code units, sharing chunks with this
+    /// rope.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn slice(&self, range: Range<usize>) -> Utf16Rope {
+        assert!(range.start <= range.end && range.end <= self.len(), "slicing out of bounds of Utf16Rope");
+        let (_, rest) = Node::split(&self.root, range.start);
+        let (middle, _) = Node::split(&rest, range.end - range.start);
+        Utf16Rope { root: middle }
+    }
+
+    /// Returns the code unit at an offset.
+    pub fn code_unit(&self, mut offset: usize) -> Option<u16> {
+        let mut node = &self.root;
+        loop {
+            match &**node {
+                Node::Leaf { chunk, .. } => return chunk.buf.get(offset).copied(),
+                Node::Branch { left, right, .. } => {
+                    if offset < left.len() {
+                        node = left;
+                    } else {
+                        offset -= left.len();
+                        node = right;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Iterates the chunks of the rope in order.
+    pub fn chunks(&self) -> Utf16RopeChunks<'_> {
+        Utf16RopeChunks {
+            stack: vec![&self.root],
+        }
+    }
+
+    /// Converts an offset between units.
+    ///
+    /// Returns `None` if the offset is out of bounds or does not fall on a
+    /// code point boundary.
+    pub fn convert_offset(&self, offset: usize, from: OffsetUnit, to: OffsetUnit) -> Option<usize> {
+        if offset > self.root.metrics().get(from) {
+            return None;
+        }
+        self.root.seek(offset, from).map(|m| m.get(to))
+    }
+
+    /// Returns the zero-based line containing an offset.
+    ///
+    /// Returns `None` if the offset is out of bounds or does not fall on a
+    /// code point boundary.
+    pub fn line_of(&self, offset: usize, unit: OffsetUnit) -> Option<usize> {
+        if offset > self.root.metrics().get(unit) {
+            return None;
+        }
+        self.root.seek(offset, unit).map(|m| m.lines)
+    }
+
+    /// Returns the offset at which a zero-based line starts, or `None` if
+    /// the line does not exist.
+    pub fn line_start(&self, line: usize, unit: OffsetUnit) -> Option<usize> {
+        if line == 0 {
+            return Some(0);
+        }
+        if line > self.root.metrics().lines {
+            return None;
+        }
+        Some(self.root.seek_line(line).get(unit))
+    }
+
+    /// Copies the rope into a contiguous string.
+    pub fn to_utf16_string(&self) -> Utf16String {
+        let mut r = Utf16String { buf: Vec::with_capacity(self.len()) };
+        for chunk in self.chunks() {
+            r.push_utf16_str(chunk);
+        }
+        r
+    }
+}
+
+impl Default for Utf16Rope {
+    fn default() -> Self {
+        Utf16Rope::new()
+    }
+}
+
+impl From<&Utf16Str> for Utf16Rope {
+    fn from(value: &Utf16Str) -> Self {
+        Utf16Rope {
+            root: Node::build(&value.raw),
+        }
+    }
+}
+
+impl From<&str> for Utf16Rope {
+    fn from(value: &str) -> Self {
+        Utf16Rope::from(&*Utf16String::from(value))
+    }
+}
+
+impl From<&Utf16Rope> for Utf16String {
+    fn from(value: &Utf16Rope) -> Self {
+        value.to_utf16_string()
+    }
+}
+
+impl PartialEq for Utf16Rope {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.chunks().flat_map(|c| c.code_units()).eq(other.chunks().flat_map(|c| c.code_units()))
+    }
+}
+
+impl Eq for Utf16Rope {}
+
+impl fmt::Display for Utf16Rope {
+    /// Writes the text, replacing lone surrogates with U+FFFD. Width and
+    /// precision apply to the whole text, not to each chunk.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units = self.chunks().flat_map(|chunk| chunk.raw.iter().copied());
+        let chars = char::decode_utf16(units).map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER));
+        if f.width().is_none() && f.precision().is_none() {
+            for ch in chars {
+                f.write_char(ch)?;
+            }
+            Ok(())
+        } else {
+            f.pad(&chars.collect::<String>())
+        }
+    }
+}
+
+impl fmt::Debug for Utf16Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_utf16_string(), f)
+    }
+}
+
+/// Iterator returned by [`Utf16Rope::chunks`].
+pub struct Utf16RopeChunks<'a> {
+    stack: Vec<&'a Arc<Node>>,
+}
+
+impl<'a> Iterator for Utf16RopeChunks<'a> {
+    type Item = &'a Utf16Str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            match &**node {
+                Node::Leaf { chunk, .. } => {
+                    if !chunk.is_empty() {
+                        return Some(chunk);
+                    }
+                },
+                Node::Branch { left, right, .. } => {
+                    self.stack.push(right);
+                    self.stack.push(left);
+                },
+            }
+        }
+        None
+    }
+}
\ No newline at end of file
@@ -0,0 +1,127 @@
+//! Serde support, enabled by the `serde` feature.
+//!
+//! `Utf16Str` and `Utf16String` serialize as a string in human-readable
+//! formats, replacing lone surrogates with U+FFFD, and as a sequence of
+//! `u16` code units in binary formats. Deserializing a `Utf16String` accepts
+//! a string, a sequence of code units or UTF-16LE bytes.
+//!
+//! Formats do not provide aligned code units, so `&Utf16Str` cannot be
+//! borrowed from the input; deserialize a `Cow<Utf16Str>` or a
+//! `Utf16String` instead.
+//!
+//! The [`lossless`] and [`utf16le_bytes`] modules select a representation
+//! through `#[serde(with = "...")]`:
+//!
+//! ```
+//! use hydroperx_utf16::Utf16String;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Entry {
+//!     #[serde(with = "hydroperx_utf16::serde::lossless")]
+//!     key: Utf16String,
+//! }
+//! ```
+
+use core::fmt;
+use alloc::vec::Vec;
+
+use ::serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use ::serde::ser::{Serialize, Serializer};
+
+use crate::{Utf16Str, Utf16String};
+
+impl Serialize for Utf16Str {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.collect_seq(&self.raw)
+        }
+    }
+}
+
+impl Serialize for Utf16String {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Utf16String {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(Utf16StringVisitor)
+        } else {
+            deserializer.deserialize_seq(Utf16StringVisitor)
+        }
+    }
+}
+
+struct Utf16StringVisitor;
+
+impl<'de> Visitor<'de> for Utf16StringVisitor {
+    type Value = Utf16String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a string, a sequence of UTF-16 code units or UTF-16LE bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Utf16String::from(v))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        if !v.len().is_multiple_of(2) {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        Ok(Utf16String {
+            buf: v.chunks_exact(2).map(|cu| u16::from_le_bytes([cu[0], cu[1]])).collect(),
+        })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut buf = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(cu) = seq.next_element::<u16>()? {
+            buf.push(cu);
+        }
+        Ok(Utf16String { buf })
+    }
+}
+
+/// Serializes as a sequence of code units in every format, preserving lone
+/// surrogates.
+pub mod lossless {
+    use super::*;
+
+    pub fn serialize<T, S>(string: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<Utf16Str> + ?Sized,
+        S: Serializer,
+    {
+        serializer.collect_seq(&string.as_ref().raw)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Utf16String, D::Error> {
+        deserializer.deserialize_seq(Utf16StringVisitor)
+    }
+}
+
+/// Serializes as UTF-16LE bytes in every format, preserving lone
+/// surrogates.
+pub mod utf16le_bytes {
+    use super::*;
+
+    pub fn serialize<T, S>(string: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<Utf16Str> + ?Sized,
+        S: Serializer,
+    {
+        let bytes: Vec<u8> = string.as_ref().raw.iter().flat_map(|cu| cu.to_le_bytes()).collect();
+        serializer.serialize_bytes(&bytes)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Utf16String, D::Error> {
+        deserializer.deserialize_bytes(Utf16StringVisitor)
+    }
+}
\ No newline at end of file
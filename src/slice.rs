@@ -1,8 +1,8 @@
 // Based in https://github.com/getsentry/utf16string/blob/main/src/slicing.rs
 
-use std::ops::{Index, IndexMut, Range, RangeFull, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive};
+use core::ops::{Index, IndexMut, Range, RangeFull, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive};
 
-use super::{Utf16Str, Utf16String};
+use super::{offset::{Utf16Offset, Utf16Range}, Utf16Str, Utf16String};
 
 mod private {
     use super::*;
@@ -15,6 +15,10 @@ mod private {
     impl SealedSliceIndex for RangeTo<usize> {}
     impl SealedSliceIndex for RangeInclusive<usize> {}
     impl SealedSliceIndex for RangeToInclusive<usize> {}
+    impl SealedSliceIndex for Utf16Range {}
+    impl SealedSliceIndex for Range<Utf16Offset> {}
+    impl SealedSliceIndex for RangeFrom<Utf16Offset> {}
+    impl SealedSliceIndex for RangeTo<Utf16Offset> {}
 }
 
 pub trait SliceIndex<T>: private::SealedSliceIndex
@@ -27,8 +31,14 @@ where
 
     fn get_mut(self, slice: &mut T) -> Option<&mut Self::Output>;
 
+    /// # Safety
+    ///
+    /// The index must be within the bounds of the slice.
     unsafe fn get_unchecked(self, slice: &T) -> &Self::Output;
 
+    /// # Safety
+    ///
+    /// The index must be within the bounds of the slice.
     unsafe fn get_unchecked_mut(self, slice: &mut T) -> &mut Self::Output;
 
     fn index(self, slice: &T) -> &Self::Output;
@@ -87,14 +97,14 @@ impl SliceIndex<Utf16Str> for Range<usize> {
     unsafe fn get_unchecked(self, slice: &Utf16Str) -> &Self::Output {
         let ptr = unsafe { slice.as_ptr().add(self.start) };
         let len = self.end - self.start;
-        unsafe { Utf16Str::from_utf16_unchecked(std::slice::from_raw_parts(ptr, len)) }
+        unsafe { Utf16Str::from_utf16_unchecked(core::slice::from_raw_parts(ptr, len)) }
     }
 
     #[inline]
     unsafe fn get_unchecked_mut(self, slice: &mut Utf16Str) -> &mut Self::Output {
         let ptr = unsafe { slice.as_mut_ptr().add(self.start) };
         let len = self.end - self.start;
-        unsafe { Utf16Str::from_utf16_unchecked_mut(std::slice::from_raw_parts_mut(ptr, len)) }
+        unsafe { Utf16Str::from_utf16_unchecked_mut(core::slice::from_raw_parts_mut(ptr, len)) }
     }
 
     #[inline]
@@ -124,13 +134,13 @@ impl SliceIndex<Utf16Str> for RangeTo<usize> {
     #[inline]
     unsafe fn get_unchecked(self, slice: &Utf16Str) -> &Self::Output {
         let ptr = slice.as_ptr();
-        unsafe { Utf16Str::from_utf16_unchecked(std::slice::from_raw_parts(ptr, self.end)) }
+        unsafe { Utf16Str::from_utf16_unchecked(core::slice::from_raw_parts(ptr, self.end)) }
     }
 
     #[inline]
     unsafe fn get_unchecked_mut(self, slice: &mut Utf16Str) -> &mut Self::Output {
         let ptr = slice.as_mut_ptr();
-        unsafe { Utf16Str::from_utf16_unchecked_mut(std::slice::from_raw_parts_mut(ptr, self.end)) }
+        unsafe { Utf16Str::from_utf16_unchecked_mut(core::slice::from_raw_parts_mut(ptr, self.end)) }
     }
 
     #[inline]
@@ -161,14 +171,14 @@ impl SliceIndex<Utf16Str> for RangeFrom<usize> {
     unsafe fn get_unchecked(self, slice: &Utf16Str) -> &Self::Output {
         let ptr = unsafe { slice.as_ptr().add(self.start) };
         let len = slice.len() - self.start;
-        unsafe { Utf16Str::from_utf16_unchecked(std::slice::from_raw_parts(ptr, len)) }
+        unsafe { Utf16Str::from_utf16_unchecked(core::slice::from_raw_parts(ptr, len)) }
     }
 
     #[inline]
     unsafe fn get_unchecked_mut(self, slice: &mut Utf16Str) -> &mut Self::Output {
         let ptr = unsafe { slice.as_mut_ptr().add(self.start) };
         let len = slice.len() - self.start;
-        unsafe { Utf16Str::from_utf16_unchecked_mut(std::slice::from_raw_parts_mut(ptr, len)) }
+        unsafe { Utf16Str::from_utf16_unchecked_mut(core::slice::from_raw_parts_mut(ptr, len)) }
     }
 
     #[inline]
@@ -270,6 +280,142 @@ impl SliceIndex<Utf16Str> for RangeToInclusive<usize> {
     }
 }
 
+impl SliceIndex<Utf16Str> for Utf16Range {
+    type Output = Utf16Str;
+
+    #[inline]
+    fn get(self, slice: &Utf16Str) -> Option<&Self::Output> {
+        (self.start.0..self.end.0).get(slice)
+    }
+
+    #[inline]
+    fn get_mut(self, slice: &mut Utf16Str) -> Option<&mut Self::Output> {
+        (self.start.0..self.end.0).get_mut(slice)
+    }
+
+    #[inline]
+    unsafe fn get_unchecked(self, slice: &Utf16Str) -> &Self::Output {
+        unsafe { (self.start.0..self.end.0).get_unchecked(slice) }
+    }
+
+    #[inline]
+    unsafe fn get_unchecked_mut(self, slice: &mut Utf16Str) -> &mut Self::Output {
+        unsafe { (self.start.0..self.end.0).get_unchecked_mut(slice) }
+    }
+
+    #[inline]
+    fn index(self, slice: &Utf16Str) -> &Self::Output {
+        (self.start.0..self.end.0).index(slice)
+    }
+
+    #[inline]
+    fn index_mut(self, slice: &mut Utf16Str) -> &mut Self::Output {
+        (self.start.0..self.end.0).index_mut(slice)
+    }
+}
+
+impl SliceIndex<Utf16Str> for Range<Utf16Offset> {
+    type Output = Utf16Str;
+
+    #[inline]
+    fn get(self, slice: &Utf16Str) -> Option<&Self::Output> {
+        (self.start.0..self.end.0).get(slice)
+    }
+
+    #[inline]
+    fn get_mut(self, slice: &mut Utf16Str) -> Option<&mut Self::Output> {
+        (self.start.0..self.end.0).get_mut(slice)
+    }
+
+    #[inline]
+    unsafe fn get_unchecked(self, slice: &Utf16Str) -> &Self::Output {
+        unsafe { (self.start.0..self.end.0).get_unchecked(slice) }
+    }
+
+    #[inline]
+    unsafe fn get_unchecked_mut(self, slice: &mut Utf16Str) -> &mut Self::Output {
+        unsafe { (self.start.0..self.end.0).get_unchecked_mut(slice) }
+    }
+
+    #[inline]
+    fn index(self, slice: &Utf16Str) -> &Self::Output {
+        (self.start.0..self.end.0).index(slice)
+    }
+
+    #[inline]
+    fn index_mut(self, slice: &mut Utf16Str) -> &mut Self::Output {
+        (self.start.0..self.end.0).index_mut(slice)
+    }
+}
+
+impl SliceIndex<Utf16Str> for RangeFrom<Utf16Offset> {
+    type Output = Utf16Str;
+
+    #[inline]
+    fn get(self, slice: &Utf16Str) -> Option<&Self::Output> {
+        (self.start.0..).get(slice)
+    }
+
+    #[inline]
+    fn get_mut(self, slice: &mut Utf16Str) -> Option<&mut Self::Output> {
+        (self.start.0..).get_mut(slice)
+    }
+
+    #[inline]
+    unsafe fn get_unchecked(self, slice: &Utf16Str) -> &Self::Output {
+        unsafe { (self.start.0..).get_unchecked(slice) }
+    }
+
+    #[inline]
+    unsafe fn get_unchecked_mut(self, slice: &mut Utf16Str) -> &mut Self::Output {
+        unsafe { (self.start.0..).get_unchecked_mut(slice) }
+    }
+
+    #[inline]
+    fn index(self, slice: &Utf16Str) -> &Self::Output {
+        (self.start.0..).index(slice)
+    }
+
+    #[inline]
+    fn index_mut(self, slice: &mut Utf16Str) -> &mut Self::Output {
+        (self.start.0..).index_mut(slice)
+    }
+}
+
+impl SliceIndex<Utf16Str> for RangeTo<Utf16Offset> {
+    type Output = Utf16Str;
+
+    #[inline]
+    fn get(self, slice: &Utf16Str) -> Option<&Self::Output> {
+        (..self.end.0).get(slice)
+    }
+
+    #[inline]
+    fn get_mut(self, slice: &mut Utf16Str) -> Option<&mut Self::Output> {
+        (..self.end.0).get_mut(slice)
+    }
+
+    #[inline]
+    unsafe fn get_unchecked(self, slice: &Utf16Str) -> &Self::Output {
+        unsafe { (..self.end.0).get_unchecked(slice) }
+    }
+
+    #[inline]
+    unsafe fn get_unchecked_mut(self, slice: &mut Utf16Str) -> &mut Self::Output {
+        unsafe { (..self.end.0).get_unchecked_mut(slice) }
+    }
+
+    #[inline]
+    fn index(self, slice: &Utf16Str) -> &Self::Output {
+        (..self.end.0).index(slice)
+    }
+
+    #[inline]
+    fn index_mut(self, slice: &mut Utf16Str) -> &mut Self::Output {
+        (..self.end.0).index_mut(slice)
+    }
+}
+
 impl<I> Index<I> for Utf16Str
 where
     I: SliceIndex<Utf16Str>
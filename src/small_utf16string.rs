@@ -0,0 +1,488 @@
+use crate::{utils::*, Utf16Str, Utf16String};
+use alloc::borrow::{Borrow, BorrowMut};
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut, Range};
+use core::ptr::NonNull;
+
+/// Number of code units stored without allocating.
+const INLINE_CAPACITY: usize = 11;
+
+/// An owned UTF-16 string that stores up to 11 code units inline
+/// and moves to the heap beyond that.
+///
+/// Indexing this type is equivalent to indexing UTF-16 code units (not bytes),
+/// which are represented by `u16`.
+///
+/// The length and capacity are stored as `u32`, so operations that would
+/// make either exceed `u32::MAX` code units panic.
+pub struct SmallUtf16String {
+    repr: Repr,
+}
+
+enum Repr {
+    Inline { len: u8, buf: [u16; INLINE_CAPACITY] },
+    /// A `Vec<u16>` taken apart, so that the string is no larger than a `Vec`.
+    Heap { ptr: NonNull<u16>, len: u32, capacity: u32 },
+}
+
+impl Repr {
+    /// Takes a `Vec` apart. Panics, dropping the `Vec`, if its length or
+    /// capacity does not fit in a `u32`.
+    fn from_vec(buf: Vec<u16>) -> Self {
+        let len = u32::try_from(buf.len()).expect("SmallUtf16String is too long");
+        let capacity = u32::try_from(buf.capacity()).expect("SmallUtf16String is too long");
+        let mut buf = ManuallyDrop::new(buf);
+        Repr::Heap {
+            ptr: NonNull::new(buf.as_mut_ptr()).unwrap(),
+            len,
+            capacity,
+        }
+    }
+
+    /// Copies at most [`INLINE_CAPACITY`] code units into an inline repr.
+    fn inline(units: &[u16]) -> Self {
+        let mut buf = [0; INLINE_CAPACITY];
+        buf[..units.len()].copy_from_slice(units);
+        Repr::Inline { len: units.len() as u8, buf }
+    }
+}
+
+unsafe impl Send for SmallUtf16String {}
+unsafe impl Sync for SmallUtf16String {}
+
+impl Drop for SmallUtf16String {
+    fn drop(&mut self) {
+        if let Repr::Heap { ptr, len, capacity } = self.repr {
+            drop(unsafe { Vec::from_raw_parts(ptr.as_ptr(), len as usize, capacity as usize) });
+        }
+    }
+}
+
+impl Clone for SmallUtf16String {
+    fn clone(&self) -> Self {
+        match &self.repr {
+            Repr::Inline { len, buf } => SmallUtf16String {
+                repr: Repr::Inline { len: *len, buf: *buf },
+            },
+            Repr::Heap { .. } => SmallUtf16String {
+                repr: Repr::from_vec(self.raw.to_vec()),
+            },
+        }
+    }
+}
+
+impl SmallUtf16String {
+    pub const fn new() -> Self {
+        SmallUtf16String {
+            repr: Repr::Inline { len: 0, buf: [0; INLINE_CAPACITY] },
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut r = SmallUtf16String::new();
+        r.reserve(capacity);
+        r
+    }
+
+    /// Returns whether the code units are stored inline.
+    #[inline]
+    pub fn is_inline(&self) -> bool {
+        matches!(self.repr, Repr::Inline { .. })
+    }
+
+    /// Returns the number of code units the string can hold without
+    /// reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        match &self.repr {
+            Repr::Inline { .. } => INLINE_CAPACITY,
+            Repr::Heap { capacity, .. } => *capacity as usize,
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more code units,
+    /// moving the string to the heap if it no longer fits inline.
+    pub fn reserve(&mut self, additional: usize) {
+        match &mut self.repr {
+            Repr::Inline { len, buf } => {
+                let len = *len as usize;
+                if len + additional > INLINE_CAPACITY {
+                    let mut heap = Vec::with_capacity(len + additional);
+                    heap.extend_from_slice(&buf[..len]);
+                    self.repr = Repr::from_vec(heap);
+                }
+            },
+            Repr::Heap { .. } => self.with_vec(|buf| buf.reserve(additional)),
+        }
+    }
+
+    /// Shrinks the capacity as much as possible, moving the string back
+    /// inline if it fits.
+    pub fn shrink_to_fit(&mut self) {
+        if self.is_inline() {
+            return;
+        }
+        if self.len() <= INLINE_CAPACITY {
+            // the heap buffer is dropped here
+            let buf = self.take_vec();
+            self.repr = Repr::inline(&buf);
+        } else {
+            self.with_vec(|buf| buf.shrink_to_fit());
+        }
+    }
+
+    #[inline]
+    pub fn as_utf16_str(&self) -> &Utf16Str {
+        self
+    }
+
+    #[inline]
+    pub fn as_mut_utf16_str(&mut self) -> &mut Utf16Str {
+        self
+    }
+
+    pub fn into_utf16_string(mut self) -> Utf16String {
+        Utf16String {
+            buf: self.take_vec(),
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        match &mut self.repr {
+            Repr::Inline { len, .. } => *len = 0,
+            Repr::Heap { len, .. } => *len = 0,
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, ch: char) {
+        let mut units = [0; 2];
+        let units = ch.encode_utf16(&mut units);
+        self.splice_in(index, units.len(), units.iter().copied());
+    }
+
+    pub fn insert_utf16_str(&mut self, index: usize, string: &Utf16Str) {
+        self.splice_in(index, string.len(), string.raw.iter().copied());
+    }
+
+    pub fn insert_utf8_str(&mut self, index: usize, string: &str) {
+        self.splice_in(index, string.utf16_len(), string.encode_utf16());
+    }
+
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        self.insert(self.len(), ch);
+    }
+
+    pub fn push_utf16_str(&mut self, string: &Utf16Str) {
+        self.insert_utf16_str(self.len(), string);
+    }
+
+    pub fn push_utf8_str(&mut self, string: &str) {
+        self.insert_utf8_str(self.len(), string);
+    }
+
+    /// Removes a surrogate pair or a code unit from the specified
+    /// index in code units, and returns the code point that was
+    /// removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn remove(&mut self, index: usize) -> char {
+        assert!(index < self.len(), "removing character out of bounds");
+        let cu1 = self[index];
+        if is_high_surrogate(cu1) && (index + 1) < self.len() {
+            let cu2 = self[index + 1];
+            if is_low_surrogate(cu2) {
+                self.remove_range(index..index + 2);
+                return decode_char(cu1, cu2);
+            }
+        }
+        self.remove_range(index..index + 1);
+        code_unit_to_char(cu1)
+    }
+
+    /// Removes the last surrogate pair or code unit, and returns the code point that was
+    /// removed.
+    pub fn pop(&mut self) -> Option<char> {
+        let l = self.len();
+        if l == 0 {
+            return None;
+        }
+        let i = if l > 1 && is_low_surrogate(self[l - 1]) && is_high_surrogate(self[l - 2]) { l - 2 } else { l - 1 };
+        Some(self.remove(i))
+    }
+
+    /// Inserts `count` code units at the given index.
+    fn splice_in(&mut self, index: usize, count: usize, units: impl Iterator<Item = u16>) {
+        assert!(index <= self.len(), "inserting out of bounds");
+        self.reserve(count);
+        match &mut self.repr {
+            Repr::Inline { len, buf } => {
+                let l = *len as usize;
+                buf.copy_within(index..l, index + count);
+                for (dst, cu) in buf[index..index + count].iter_mut().zip(units) {
+                    *dst = cu;
+                }
+                *len += count as u8;
+            },
+            Repr::Heap { .. } => self.with_vec(|buf| {
+                buf.splice(index..index, units);
+            }),
+        }
+    }
+
+    fn remove_range(&mut self, range: Range<usize>) {
+        match &mut self.repr {
+            Repr::Inline { len, buf } => {
+                let l = *len as usize;
+                buf.copy_within(range.end..l, range.start);
+                *len -= range.len() as u8;
+            },
+            Repr::Heap { .. } => self.with_vec(|buf| {
+                buf.drain(range);
+            }),
+        }
+    }
+
+    /// Moves the code units out into a `Vec`, leaving the string empty.
+    fn take_vec(&mut self) -> Vec<u16> {
+        let repr = core::mem::replace(&mut self.repr, Repr::Inline { len: 0, buf: [0; INLINE_CAPACITY] });
+        match repr {
+            Repr::Inline { len, buf } => buf[..len as usize].to_vec(),
+            Repr::Heap { ptr, len, capacity } => unsafe { Vec::from_raw_parts(ptr.as_ptr(), len as usize, capacity as usize) },
+        }
+    }
+
+    /// Operates on the heap buffer as a `Vec`. The string is left empty if
+    /// `f` panics.
+    fn with_vec<R>(&mut self, f: impl FnOnce(&mut Vec<u16>) -> R) -> R {
+        let mut buf = self.take_vec();
+        let r = f(&mut buf);
+        self.repr = Repr::from_vec(buf);
+        r
+    }
+}
+
+impl Deref for SmallUtf16String {
+    type Target = Utf16Str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        let raw = match &self.repr {
+            Repr::Inline { len, buf } => &buf[..*len as usize],
+            Repr::Heap { ptr, len, .. } => unsafe { core::slice::from_raw_parts(ptr.as_ptr(), *len as usize) },
+        };
+        unsafe { Utf16Str::from_utf16_unchecked(raw) }
+    }
+}
+
+impl DerefMut for SmallUtf16String {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let raw = match &mut self.repr {
+            Repr::Inline { len, buf } => &mut buf[..*len as usize],
+            Repr::Heap { ptr, len, .. } => unsafe { core::slice::from_raw_parts_mut(ptr.as_ptr(), *len as usize) },
+        };
+        unsafe { Utf16Str::from_utf16_unchecked_mut(raw) }
+    }
+}
+
+impl Default for SmallUtf16String {
+    fn default() -> Self {
+        SmallUtf16String::new()
+    }
+}
+
+impl From<&str> for SmallUtf16String {
+    fn from(value: &str) -> Self {
+        let mut r = SmallUtf16String::new();
+        r.push_utf8_str(value);
+        r
+    }
+}
+
+impl From<&Utf16Str> for SmallUtf16String {
+    fn from(value: &Utf16Str) -> Self {
+        let mut r = SmallUtf16String::new();
+        r.push_utf16_str(value);
+        r
+    }
+}
+
+impl From<Utf16String> for SmallUtf16String {
+    /// Keeps the heap buffer of the string, unless it fits inline.
+    fn from(value: Utf16String) -> Self {
+        let repr = if value.len() <= INLINE_CAPACITY {
+            Repr::inline(&value.buf)
+        } else {
+            Repr::from_vec(value.buf)
+        };
+        SmallUtf16String { repr }
+    }
+}
+
+impl From<SmallUtf16String> for Utf16String {
+    fn from(value: SmallUtf16String) -> Self {
+        value.into_utf16_string()
+    }
+}
+
+impl From<char> for SmallUtf16String {
+    fn from(value: char) -> Self {
+        let mut r = SmallUtf16String::new();
+        r.push(value);
+        r
+    }
+}
+
+impl FromIterator<char> for SmallUtf16String {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut r = SmallUtf16String::new();
+        r.extend(iter);
+        r
+    }
+}
+
+impl Extend<char> for SmallUtf16String {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        for ch in iter {
+            self.push(ch);
+        }
+    }
+}
+
+impl<'a> Extend<&'a str> for SmallUtf16String {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        for string in iter {
+            self.push_utf8_str(string);
+        }
+    }
+}
+
+impl<'a> Extend<&'a Utf16Str> for SmallUtf16String {
+    fn extend<I: IntoIterator<Item = &'a Utf16Str>>(&mut self, iter: I) {
+        for string in iter {
+            self.push_utf16_str(string);
+        }
+    }
+}
+
+impl Borrow<Utf16Str> for SmallUtf16String {
+    #[inline]
+    fn borrow(&self) -> &Utf16Str {
+        self
+    }
+}
+
+impl BorrowMut<Utf16Str> for SmallUtf16String {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut Utf16Str {
+        self
+    }
+}
+
+impl AsRef<Utf16Str> for SmallUtf16String {
+    #[inline]
+    fn as_ref(&self) -> &Utf16Str {
+        self
+    }
+}
+
+impl AsRef<[u16]> for SmallUtf16String {
+    #[inline]
+    fn as_ref(&self) -> &[u16] {
+        &self.raw
+    }
+}
+
+impl PartialEq for SmallUtf16String {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for SmallUtf16String {}
+
+impl PartialOrd for SmallUtf16String {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SmallUtf16String {
+    /// Compares code units; see [`Utf16Str`].
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl Hash for SmallUtf16String {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl PartialEq<Utf16Str> for SmallUtf16String {
+    #[inline]
+    fn eq(&self, other: &Utf16Str) -> bool {
+        **self == *other
+    }
+}
+
+impl PartialEq<Utf16String> for SmallUtf16String {
+    #[inline]
+    fn eq(&self, other: &Utf16String) -> bool {
+        **self == **other
+    }
+}
+
+impl PartialEq<str> for SmallUtf16String {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        **self == *other
+    }
+}
+
+impl PartialEq<&str> for SmallUtf16String {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        **self == **other
+    }
+}
+
+impl fmt::Display for SmallUtf16String {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl fmt::Debug for SmallUtf16String {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Write for SmallUtf16String {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_utf8_str(s);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
\ No newline at end of file
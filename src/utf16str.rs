@@ -1,4 +1,10 @@
-use crate::{slice::SliceIndex, Utf16CharIndices, Utf16Chars, Utf16Str, Utf16String};
+use crate::{iterators::{Escape, EscapeMode}, slice::SliceIndex, utils::*, Utf16CharIndices, Utf16Chars, Utf16EscapeDebug, Utf16EscapeDefault, Utf16EscapeUnicode, Utf16Str, Utf16String};
+use core::cmp::Ordering;
+use core::ops::Range;
+use alloc::rc::Rc;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use alloc::{borrow::ToOwned, boxed::Box, string::String};
 
 impl Utf16Str {
     /// Returns the number of UTF-16 code units representing the string.
@@ -17,29 +23,62 @@ impl Utf16Str {
         self.raw.as_mut_ptr()
     }
 
+    /// Converts a slice of code units into a string slice without
+    /// performing any check.
+    ///
+    /// # Safety
+    ///
+    /// This function is currently always safe to call, as `Utf16Str`
+    /// does not enforce well-formed UTF-16.
     pub unsafe fn from_utf16_unchecked(raw: &[u16]) -> &Self {
         unsafe { &*(raw as *const [u16] as *const Self) }
     }
 
+    /// Converts a mutable slice of code units into a mutable string slice
+    /// without performing any check.
+    ///
+    /// # Safety
+    ///
+    /// This function is currently always safe to call, as `Utf16Str`
+    /// does not enforce well-formed UTF-16.
     pub unsafe fn from_utf16_unchecked_mut(raw: &mut [u16]) -> &mut Self {
         unsafe { &mut *(raw as *mut [u16] as *mut Self) }
     }
 
     /// Iterates the UTF-16 code units.
-    pub fn code_units(&self) -> std::slice::Iter<u16> {
+    pub fn code_units(&self) -> core::slice::Iter<'_, u16> {
         self.raw.iter()
     }
 
-    /// Iterates the code points in the string.
-    pub fn chars(&self) -> Utf16Chars {
+    /// Iterates the code points in the string. Lone surrogates are
+    /// replaced with U+FFFD.
+    pub fn chars(&self) -> Utf16Chars<'_> {
         Utf16Chars {
             slice: self,
             index: 0,
         }
     }
 
+    /// Escapes each code point with `char::escape_debug`, as
+    /// `str::escape_debug` does. Lone surrogates are escaped as `\u{D800}`.
+    pub fn escape_debug(&self) -> Utf16EscapeDebug<'_> {
+        Utf16EscapeDebug(Escape::new(self, EscapeMode::Debug))
+    }
+
+    /// Escapes each code point with `char::escape_default`. Lone surrogates
+    /// are escaped as `\u{D800}`.
+    pub fn escape_default(&self) -> Utf16EscapeDefault<'_> {
+        Utf16EscapeDefault(Escape::new(self, EscapeMode::Default))
+    }
+
+    /// Escapes each code point with `char::escape_unicode`. Lone surrogates
+    /// are escaped as `\u{D800}`.
+    pub fn escape_unicode(&self) -> Utf16EscapeUnicode<'_> {
+        Utf16EscapeUnicode(Escape::new(self, EscapeMode::Unicode))
+    }
+
     /// Iterates the indices and their code pointss in the string.
-    pub fn char_indices(&self) -> Utf16CharIndices {
+    pub fn char_indices(&self) -> Utf16CharIndices<'_> {
         Utf16CharIndices {
             slice: self,
             index: 0,
@@ -56,11 +95,21 @@ impl Utf16Str {
         index.get_mut(self)
     }
 
+    /// Returns a subslice of the string without bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// The index must be within the bounds of the string.
     #[inline]
     pub unsafe fn get_unchecked<I: SliceIndex<Utf16Str>>(&self, index: I) -> &<I as SliceIndex<Utf16Str>>::Output {
         unsafe { index.get_unchecked(self) }
     }
 
+    /// Returns a mutable subslice of the string without bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// The index must be within the bounds of the string.
     #[inline]
     pub unsafe fn get_unchecked_mut<I: SliceIndex<Utf16Str>>(&mut self, index: I) -> &mut <I as SliceIndex<Utf16Str>>::Output {
         unsafe { index.get_unchecked_mut(self) }
@@ -71,20 +120,111 @@ impl Utf16Str {
         self.raw.len() == 0
     }
 
-    pub fn to_owned(&self) -> Utf16String {
+    /// Converts a boxed string slice into an owned string without copying
+    /// or allocating.
+    pub fn into_utf16_string(self: Box<Self>) -> Utf16String {
+        let raw = Box::into_raw(self) as *mut [u16];
         Utf16String {
-            buf: self.raw.to_owned(),
+            buf: unsafe { Box::from_raw(raw) }.into_vec(),
         }
     }
 
+    /// Converts into UTF-8, replacing lone surrogates with U+FFFD. Use
+    /// [`to_wtf8`](Self::to_wtf8) to preserve them.
     pub fn to_utf8(&self) -> String {
-        let mut r = String::new();
+        let mut r = String::with_capacity(self.utf8_len());
         for ch in self.chars() {
             r.push(ch);
         }
         r
     }
 
+    /// Returns the number of UTF-8 bytes needed to encode the string.
+    /// A lone surrogate is counted as U+FFFD.
+    pub fn utf8_len(&self) -> usize {
+        utf8_len_of_code_units(&self.raw)
+    }
+
+    /// Returns the number of code points in the string, counting a lone
+    /// surrogate as one. Unlike `chars().count()`, this does not decode
+    /// the string.
+    pub fn char_count(&self) -> usize {
+        char_count_of_code_units(&self.raw)
+    }
+
+    /// Returns the code point at the given code point index, or `None` if
+    /// the index is out of bounds. A lone surrogate is returned as U+FFFD.
+    pub fn nth_char(&self, n: usize) -> Option<char> {
+        let offset = self.char_offset_to_code_unit(n).ok()?;
+        self[offset..].chars().next()
+    }
+
+    /// Returns the code unit offset of the given code point offset, as
+    /// needed to slice a string indexed by code points. The length of the
+    /// string in code points maps to its length in code units.
+    pub fn char_offset_to_code_unit(&self, char_offset: usize) -> Result<usize, OffsetError> {
+        let raw = &self.raw;
+        let mut i = 0;
+        for _ in 0..char_offset {
+            if i >= raw.len() {
+                return Err(OffsetError::OutOfBounds(char_offset));
+            }
+            i += if is_high_surrogate(raw[i]) && raw.get(i + 1).is_some_and(|cu| is_low_surrogate(*cu)) { 2 } else { 1 };
+        }
+        Ok(i)
+    }
+
+    /// Returns the code point offset of the given code unit offset.
+    ///
+    /// Fails if the offset is out of bounds or splits a surrogate pair.
+    pub fn code_unit_to_char_offset(&self, utf16offset: usize) -> Result<usize, OffsetError> {
+        let raw = &self.raw;
+        if utf16offset > raw.len() {
+            return Err(OffsetError::OutOfBounds(utf16offset));
+        }
+        if utf16offset > 0 && utf16offset < raw.len() && is_high_surrogate(raw[utf16offset - 1]) && is_low_surrogate(raw[utf16offset]) {
+            return Err(OffsetError::NotCharBoundary(utf16offset));
+        }
+        Ok(char_count_of_code_units(&raw[..utf16offset]))
+    }
+
+    /// Returns the UTF-8 offset equivalent to the given UTF-16 offset.
+    pub fn utf8_offset_of(&self, utf16offset: usize) -> Result<usize, OffsetError> {
+        utf16_offsets_as_utf8_offsets(self, [utf16offset]).next().unwrap()
+    }
+
+    /// Returns the UTF-8 range equivalent to the given UTF-16 range.
+    pub fn utf8_range_of(&self, utf16range: Range<usize>) -> Result<Range<usize>, OffsetError> {
+        let mut offsets = utf16_offsets_as_utf8_offsets(self, [utf16range.start, utf16range.end]);
+        let start = offsets.next().unwrap()?;
+        let end = offsets.next().unwrap()?;
+        Ok(start..end)
+    }
+
+    /// Compares code units, as JavaScript's `<` operator and the `Ord`
+    /// implementation do.
+    #[inline]
+    pub fn cmp_code_units(&self, other: &Utf16Str) -> Ordering {
+        self.raw.cmp(&other.raw)
+    }
+
+    /// Compares code points, giving the order of the equivalent `str`s.
+    /// A lone surrogate compares as its code unit value.
+    pub fn cmp_code_points(&self, other: &Utf16Str) -> Ordering {
+        let mut i = self.raw.iter().zip(&other.raw).position(|(a, b)| a != b).unwrap_or(self.len().min(other.len()));
+        // start decoding at the pair the first difference is part of
+        if i > 0 && is_high_surrogate(self.raw[i - 1]) {
+            i -= 1;
+        }
+        code_points(&self.raw[i..]).cmp(code_points(&other.raw[i..]))
+    }
+
+    /// Compares code points against a `str` without allocating, giving the
+    /// order of [`cmp_code_points`](Self::cmp_code_points).
+    pub fn cmp_with_str(&self, other: &str) -> Ordering {
+        code_points(&self.raw).cmp(other.chars().map(u32::from))
+    }
+
     pub fn to_lowercase(&self) -> Utf16String {
         self.to_utf8().to_lowercase().into()
     }
@@ -94,10 +234,180 @@ impl Utf16Str {
     }
 }
 
-impl std::ops::Index<usize> for Utf16Str {
+/// Decodes code units into code points, keeping lone surrogates.
+fn code_points(raw: &[u16]) -> impl Iterator<Item = u32> + '_ {
+    char::decode_utf16(raw.iter().copied()).map(|r| r.map_or_else(|e| e.unpaired_surrogate() as u32, u32::from))
+}
+
+impl core::ops::Index<usize> for Utf16Str {
     type Output = u16;
 
     fn index(&self, index: usize) -> &Self::Output {
         self.raw.get(index).expect("Reading position out of bounds of Utf16Str.")
     }
+}
+
+impl ToOwned for Utf16Str {
+    type Owned = Utf16String;
+
+    #[inline]
+    fn to_owned(&self) -> Utf16String {
+        Utf16String {
+            buf: self.raw.to_owned(),
+        }
+    }
+}
+
+impl Default for &Utf16Str {
+    #[inline]
+    fn default() -> Self {
+        unsafe { Utf16Str::from_utf16_unchecked(&[]) }
+    }
+}
+
+impl Default for &mut Utf16Str {
+    #[inline]
+    fn default() -> Self {
+        unsafe { Utf16Str::from_utf16_unchecked_mut(&mut []) }
+    }
+}
+
+impl AsRef<Utf16Str> for Utf16Str {
+    #[inline]
+    fn as_ref(&self) -> &Utf16Str {
+        self
+    }
+}
+
+impl AsMut<Utf16Str> for Utf16Str {
+    #[inline]
+    fn as_mut(&mut self) -> &mut Utf16Str {
+        self
+    }
+}
+
+impl AsRef<[u16]> for Utf16Str {
+    #[inline]
+    fn as_ref(&self) -> &[u16] {
+        &self.raw
+    }
+}
+
+impl PartialEq<Utf16String> for Utf16Str {
+    #[inline]
+    fn eq(&self, other: &Utf16String) -> bool {
+        self.raw == other.buf
+    }
+}
+
+impl PartialEq<Utf16String> for &Utf16Str {
+    #[inline]
+    fn eq(&self, other: &Utf16String) -> bool {
+        self.raw == other.buf
+    }
+}
+
+impl PartialEq<str> for Utf16Str {
+    /// Compares the code units of both strings without allocating.
+    fn eq(&self, other: &str) -> bool {
+        self.raw.iter().copied().eq(other.encode_utf16())
+    }
+}
+
+impl PartialEq<&str> for Utf16Str {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialEq<String> for Utf16Str {
+    #[inline]
+    fn eq(&self, other: &String) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialEq<Utf16Str> for str {
+    #[inline]
+    fn eq(&self, other: &Utf16Str) -> bool {
+        *other == *self
+    }
+}
+
+impl PartialEq<Utf16Str> for &str {
+    #[inline]
+    fn eq(&self, other: &Utf16Str) -> bool {
+        *other == **self
+    }
+}
+
+impl PartialEq<&Utf16Str> for str {
+    #[inline]
+    fn eq(&self, other: &&Utf16Str) -> bool {
+        **other == *self
+    }
+}
+
+impl PartialEq<Utf16Str> for String {
+    #[inline]
+    fn eq(&self, other: &Utf16Str) -> bool {
+        *other == **self
+    }
+}
+
+impl Clone for Box<Utf16Str> {
+    fn clone(&self) -> Self {
+        Box::<Utf16Str>::from(&**self)
+    }
+}
+
+impl Default for Box<Utf16Str> {
+    fn default() -> Self {
+        Box::<Utf16Str>::from(<&Utf16Str>::default())
+    }
+}
+
+impl From<&Utf16Str> for Box<Utf16Str> {
+    fn from(value: &Utf16Str) -> Self {
+        let raw = Box::into_raw(Box::<[u16]>::from(&value.raw)) as *mut Utf16Str;
+        unsafe { Box::from_raw(raw) }
+    }
+}
+
+impl From<Utf16String> for Box<Utf16Str> {
+    #[inline]
+    fn from(value: Utf16String) -> Self {
+        value.into_boxed_utf16_str()
+    }
+}
+
+impl From<&Utf16Str> for Rc<Utf16Str> {
+    fn from(value: &Utf16Str) -> Self {
+        let raw = Rc::into_raw(Rc::<[u16]>::from(&value.raw)) as *const Utf16Str;
+        unsafe { Rc::from_raw(raw) }
+    }
+}
+
+impl From<Utf16String> for Rc<Utf16Str> {
+    fn from(value: Utf16String) -> Self {
+        let raw = Rc::into_raw(Rc::<[u16]>::from(value.buf)) as *const Utf16Str;
+        unsafe { Rc::from_raw(raw) }
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl From<&Utf16Str> for Arc<Utf16Str> {
+    fn from(value: &Utf16Str) -> Self {
+        let raw = Arc::into_raw(Arc::<[u16]>::from(&value.raw)) as *const Utf16Str;
+        unsafe { Arc::from_raw(raw) }
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl From<Utf16String> for Arc<Utf16Str> {
+    fn from(value: Utf16String) -> Self {
+        let raw = Arc::into_raw(Arc::<[u16]>::from(value.buf)) as *const Utf16Str;
+        unsafe { Arc::from_raw(raw) }
+    }
 }
\ No newline at end of file
@@ -1,5 +1,9 @@
 use crate::{utils::*, Utf16Str, Utf16String};
-use std::ops::{Deref, DerefMut};
+use alloc::borrow::{Borrow, BorrowMut, Cow};
+use alloc::{borrow::ToOwned, boxed::Box, string::String, vec, vec::Vec};
+use core::convert::Infallible;
+use core::ops::{Add, AddAssign, Deref, DerefMut};
+use core::str::FromStr;
 
 impl Deref for Utf16String {
     type Target = Utf16Str;
@@ -16,14 +20,71 @@ impl DerefMut for Utf16String {
     }
 }
 
-impl<T: AsRef<str>> From<T> for Utf16String {
-    fn from(value: T) -> Self {
+impl From<&str> for Utf16String {
+    fn from(value: &str) -> Self {
         let mut r = Utf16String::new();
-        r.push_utf8_str(value.as_ref());
+        r.push_utf8_str(value);
         r
     }
 }
 
+impl From<&mut str> for Utf16String {
+    fn from(value: &mut str) -> Self {
+        Utf16String::from(&*value)
+    }
+}
+
+impl From<String> for Utf16String {
+    fn from(value: String) -> Self {
+        Utf16String::from(value.as_str())
+    }
+}
+
+impl From<&String> for Utf16String {
+    fn from(value: &String) -> Self {
+        Utf16String::from(value.as_str())
+    }
+}
+
+impl From<Box<str>> for Utf16String {
+    fn from(value: Box<str>) -> Self {
+        Utf16String::from(&*value)
+    }
+}
+
+impl From<Cow<'_, str>> for Utf16String {
+    fn from(value: Cow<'_, str>) -> Self {
+        Utf16String::from(&*value)
+    }
+}
+
+impl From<char> for Utf16String {
+    fn from(value: char) -> Self {
+        let mut r = Utf16String::new();
+        r.push(value);
+        r
+    }
+}
+
+impl From<&Utf16Str> for Utf16String {
+    fn from(value: &Utf16Str) -> Self {
+        value.to_owned()
+    }
+}
+
+impl From<Box<Utf16Str>> for Utf16String {
+    #[inline]
+    fn from(value: Box<Utf16Str>) -> Self {
+        value.into_utf16_string()
+    }
+}
+
+impl From<Cow<'_, Utf16Str>> for Utf16String {
+    fn from(value: Cow<'_, Utf16Str>) -> Self {
+        value.into_owned()
+    }
+}
+
 impl From<Utf16String> for String {
     fn from(value: Utf16String) -> Self {
         value.to_utf8()
@@ -37,11 +98,23 @@ impl Utf16String {
         }
     }
 
+    /// Converts into a boxed string slice, dropping any excess capacity.
+    pub fn into_boxed_utf16_str(self) -> Box<Utf16Str> {
+        let raw = Box::into_raw(self.buf.into_boxed_slice()) as *mut Utf16Str;
+        unsafe { Box::from_raw(raw) }
+    }
+
     #[inline]
     pub fn as_mut_utf16_str(&mut self) -> &mut Utf16Str {
         self
     }
 
+    /// Returns a mutable reference to the underlying code unit buffer.
+    ///
+    /// # Safety
+    ///
+    /// This function is currently always safe to call, as `Utf16String`
+    /// does not enforce well-formed UTF-16.
     #[inline]
     pub unsafe fn as_mut_vec(&mut self) -> &mut Vec<u16> {
         &mut self.buf
@@ -85,14 +158,13 @@ impl Utf16String {
     }
 
     pub fn push_utf8_str(&mut self, string: &str) {
-        for ch in string.chars() {
-            self.buf.extend(encode_char(ch));
-        }
+        self.buf.reserve(string.utf16_len());
+        self.buf.extend(string.encode_utf16());
     }
 
     /// Removes a surrogate pair or a code unit from the specified
     /// index in code units, and returns the code point that was
-    /// removed.
+    /// removed. A lone surrogate is returned as U+FFFD.
     /// 
     /// # Panics
     /// 
@@ -109,11 +181,11 @@ impl Utf16String {
             }
         }
         self.buf.remove(index);
-        unsafe { char::from_u32_unchecked(cu1 as u32) }
+        code_unit_to_char(cu1)
     }
 
     /// Removes the last surrogate pair or code unit, and returns the code point that was
-    /// removed.
+    /// removed. A lone surrogate is returned as U+FFFD.
     pub fn pop(&mut self) -> Option<char> {
         let l = self.len();
         if l == 0 {
@@ -131,12 +203,219 @@ impl Utf16String {
             }
         }
         self.buf.remove(i);
-        Some(unsafe { char::from_u32_unchecked(cu2 as u32) })
+        Some(code_unit_to_char(cu2))
+    }
+}
+
+impl Borrow<Utf16Str> for Utf16String {
+    #[inline]
+    fn borrow(&self) -> &Utf16Str {
+        self
     }
 }
 
-impl Default for Utf16String {
-    fn default() -> Self {
-        Utf16String::new()
+impl BorrowMut<Utf16Str> for Utf16String {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut Utf16Str {
+        self
+    }
+}
+
+impl AsRef<Utf16Str> for Utf16String {
+    #[inline]
+    fn as_ref(&self) -> &Utf16Str {
+        self
+    }
+}
+
+impl AsMut<Utf16Str> for Utf16String {
+    #[inline]
+    fn as_mut(&mut self) -> &mut Utf16Str {
+        self
+    }
+}
+
+impl AsRef<[u16]> for Utf16String {
+    #[inline]
+    fn as_ref(&self) -> &[u16] {
+        &self.buf
+    }
+}
+
+impl FromStr for Utf16String {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Utf16String::from(s))
+    }
+}
+
+impl FromIterator<char> for Utf16String {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut r = Utf16String::new();
+        r.extend(iter);
+        r
+    }
+}
+
+impl<'a> FromIterator<&'a char> for Utf16String {
+    fn from_iter<I: IntoIterator<Item = &'a char>>(iter: I) -> Self {
+        let mut r = Utf16String::new();
+        r.extend(iter);
+        r
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Utf16String {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut r = Utf16String::new();
+        r.extend(iter);
+        r
+    }
+}
+
+impl<'a> FromIterator<&'a Utf16Str> for Utf16String {
+    fn from_iter<I: IntoIterator<Item = &'a Utf16Str>>(iter: I) -> Self {
+        let mut r = Utf16String::new();
+        r.extend(iter);
+        r
+    }
+}
+
+impl FromIterator<Utf16String> for Utf16String {
+    fn from_iter<I: IntoIterator<Item = Utf16String>>(iter: I) -> Self {
+        let mut r = Utf16String::new();
+        r.extend(iter);
+        r
+    }
+}
+
+impl Extend<char> for Utf16String {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.buf.reserve(iter.size_hint().0);
+        for ch in iter {
+            self.push(ch);
+        }
+    }
+}
+
+impl<'a> Extend<&'a char> for Utf16String {
+    fn extend<I: IntoIterator<Item = &'a char>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<'a> Extend<&'a str> for Utf16String {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        for string in iter {
+            self.push_utf8_str(string);
+        }
+    }
+}
+
+impl<'a> Extend<&'a Utf16Str> for Utf16String {
+    fn extend<I: IntoIterator<Item = &'a Utf16Str>>(&mut self, iter: I) {
+        for string in iter {
+            self.push_utf16_str(string);
+        }
+    }
+}
+
+impl Extend<Utf16String> for Utf16String {
+    fn extend<I: IntoIterator<Item = Utf16String>>(&mut self, iter: I) {
+        for string in iter {
+            self.push_utf16_str(&string);
+        }
+    }
+}
+
+impl Add<&Utf16Str> for Utf16String {
+    type Output = Utf16String;
+
+    #[inline]
+    fn add(mut self, rhs: &Utf16Str) -> Self::Output {
+        self.push_utf16_str(rhs);
+        self
+    }
+}
+
+impl Add<&str> for Utf16String {
+    type Output = Utf16String;
+
+    #[inline]
+    fn add(mut self, rhs: &str) -> Self::Output {
+        self.push_utf8_str(rhs);
+        self
+    }
+}
+
+impl AddAssign<&Utf16Str> for Utf16String {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Utf16Str) {
+        self.push_utf16_str(rhs);
+    }
+}
+
+impl AddAssign<&str> for Utf16String {
+    #[inline]
+    fn add_assign(&mut self, rhs: &str) {
+        self.push_utf8_str(rhs);
+    }
+}
+
+impl PartialEq<Utf16Str> for Utf16String {
+    #[inline]
+    fn eq(&self, other: &Utf16Str) -> bool {
+        self.buf == other.raw
+    }
+}
+
+impl PartialEq<&Utf16Str> for Utf16String {
+    #[inline]
+    fn eq(&self, other: &&Utf16Str) -> bool {
+        self.buf == other.raw
+    }
+}
+
+impl PartialEq<str> for Utf16String {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        **self == *other
+    }
+}
+
+impl PartialEq<&str> for Utf16String {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        **self == **other
+    }
+}
+
+impl PartialEq<String> for Utf16String {
+    #[inline]
+    fn eq(&self, other: &String) -> bool {
+        **self == **other
+    }
+}
+
+impl PartialEq<Utf16String> for str {
+    #[inline]
+    fn eq(&self, other: &Utf16String) -> bool {
+        **other == *self
+    }
+}
+
+impl PartialEq<Utf16String> for &str {
+    #[inline]
+    fn eq(&self, other: &Utf16String) -> bool {
+        **other == **self
+    }
+}
+
+impl PartialEq<Utf16String> for String {
+    #[inline]
+    fn eq(&self, other: &Utf16String) -> bool {
+        **other == **self
     }
 }
\ No newline at end of file
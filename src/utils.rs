@@ -1,4 +1,6 @@
 use crate::Utf16Str;
+use core::ops::Range;
+use alloc::{vec, vec::Vec};
 
 /// Encodes a code point into a sequence of code units.
 /// The maximum number of elements returned is 2.
@@ -7,7 +9,7 @@ pub fn encode_char(ch: char) -> Vec<u16> {
     if val >> 16 == 0 {
         return vec![val as u16];
     }
-    val = val - 0x10000;
+    val -= 0x10000;
     let hi = (val >> 10) + 0xD800;
     let low = (val & 0b1111111111) + 0xDC00;
     vec![hi as u16, low as u16]
@@ -20,6 +22,19 @@ pub fn decode_char(hi: u16, low: u16) -> char {
     char::from_u32(hi + low + 0x10000).unwrap_or('\x00')
 }
 
+/// Converts a code unit that is not part of a surrogate pair into a code
+/// point, replacing a lone surrogate with U+FFFD.
+#[inline]
+pub(crate) fn code_unit_to_char(cu: u16) -> char {
+    char::from_u32(cu as u32).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+/// Determines whether a code unit is ECMAScript `WhiteSpace` or
+/// `LineTerminator`.
+pub(crate) fn is_js_whitespace(cu: u16) -> bool {
+    matches!(cu, 0x09..=0x0D | 0x20 | 0xA0 | 0x1680 | 0x2000..=0x200A | 0x2028 | 0x2029 | 0x202F | 0x205F | 0x3000 | 0xFEFF)
+}
+
 /// Determines whether an UTF-16 code unit is a high surrogate.
 #[inline]
 pub fn is_high_surrogate(cu: u16) -> bool {
@@ -32,16 +47,46 @@ pub fn is_low_surrogate(cu: u16) -> bool {
     cu >> 10 == 0b110111
 }
 
+/// Returns the number of code points in the given code units, counting a
+/// lone surrogate as one. The slice must not start in the middle of a
+/// surrogate pair.
+pub(crate) fn char_count_of_code_units(raw: &[u16]) -> usize {
+    let Some(rest) = raw.get(1..) else {
+        return 0;
+    };
+    // every unit except the low half of a pair starts a code point; the
+    // non-short-circuiting `&` keeps the loop free of branches
+    1 + raw.iter().zip(rest).map(|(a, b)| !(is_high_surrogate(*a) & is_low_surrogate(*b)) as usize).sum::<usize>()
+}
+
+/// Returns the number of UTF-8 bytes needed to encode the given code units,
+/// counting a lone surrogate as U+FFFD. The slice must not start in the
+/// middle of a surrogate pair.
+pub(crate) fn utf8_len_of_code_units(raw: &[u16]) -> usize {
+    let mut len = 0;
+    for (i, &cu) in raw.iter().enumerate() {
+        // a surrogate pair is 4 bytes, counted as 3 + 1
+        len += if cu < 0x80 {
+            1
+        } else if cu < 0x800 {
+            2
+        } else if is_low_surrogate(cu) && i > 0 && is_high_surrogate(raw[i - 1]) {
+            1
+        } else {
+            3
+        };
+    }
+    len
+}
+
 /// Assuming two strings are equal but in different encodings, returns
 /// the UTF-16 offset equivalent to the given UTF-8 offset.
 pub fn utf8_offset_as_utf16_offset(utf16string: &Utf16Str, utf8string: &str, utf8offset: usize) -> usize {
     let offset = utf8string[..utf8offset].chars().count();
-    let mut i = 0usize;
-    for (j, _) in utf16string.char_indices() {
+    for (i, (j, _)) in utf16string.char_indices().enumerate() {
         if i == offset {
             return j;
         }
-        i += 1;
     }
     utf16string.len()
 }
@@ -52,7 +97,7 @@ pub fn utf8_offset_as_utf16_offset(utf16string: &Utf16Str, utf8string: &str, utf
 pub fn two_utf8_offsets_as_utf16_offsets(utf16string: &Utf16Str, utf8string: &str, utf8offset1: usize, utf8offset2: usize) -> (usize, usize) {
     let mut chars = utf8string[..utf8offset2].char_indices();
     let mut offset1 = 0usize;
-    while let Some((j, _)) = chars.next() {
+    for (j, _) in chars.by_ref() {
         if j == utf8offset1 {
             break;
         }
@@ -62,7 +107,7 @@ pub fn two_utf8_offsets_as_utf16_offsets(utf16string: &Utf16Str, utf8string: &st
     let mut i = 0usize;
     let mut utf16offset1 = 0;
     let mut chars = utf16string.char_indices();
-    while let Some((j, _)) = chars.next() {
+    for (j, _) in chars.by_ref() {
         if i == offset1 {
             utf16offset1 = j;
             break;
@@ -70,7 +115,7 @@ pub fn two_utf8_offsets_as_utf16_offsets(utf16string: &Utf16Str, utf8string: &st
         i += 1;
     }
     let mut utf16offset2 = 0;
-    while let Some((j, _)) = chars.next() {
+    for (j, _) in chars {
         if i == offset2 {
             utf16offset2 = j;
             break;
@@ -84,12 +129,10 @@ pub fn two_utf8_offsets_as_utf16_offsets(utf16string: &Utf16Str, utf8string: &st
 /// the UTF-8 offset equivalent to the given UTF-16 offset.
 pub fn utf16_offset_as_utf8_offset(utf8string: &str, utf16string: &Utf16Str, utf16offset: usize) -> usize {
     let offset = utf16string[..utf16offset].chars().count();
-    let mut i = 0usize;
-    for (j, _) in utf8string.char_indices() {
+    for (i, (j, _)) in utf8string.char_indices().enumerate() {
         if i == offset {
             return j;
         }
-        i += 1;
     }
     utf8string.len()
 }
@@ -100,7 +143,7 @@ pub fn utf16_offset_as_utf8_offset(utf8string: &str, utf16string: &Utf16Str, utf
 pub fn two_utf16_offsets_as_utf8_offsets(utf8string: &str, utf16string: &Utf16Str, utf16offset1: usize, utf16offset2: usize) -> (usize, usize) {
     let mut chars = utf16string[..utf16offset2].char_indices();
     let mut offset1 = 0usize;
-    while let Some((j, _)) = chars.next() {
+    for (j, _) in chars.by_ref() {
         if j == utf16offset1 {
             break;
         }
@@ -110,7 +153,7 @@ pub fn two_utf16_offsets_as_utf8_offsets(utf8string: &str, utf16string: &Utf16St
     let mut i = 0usize;
     let mut utf8offset1 = 0;
     let mut chars = utf8string.char_indices();
-    while let Some((j, _)) = chars.next() {
+    for (j, _) in chars.by_ref() {
         if i == offset1 {
             utf8offset1 = j;
             break;
@@ -118,7 +161,7 @@ pub fn two_utf16_offsets_as_utf8_offsets(utf8string: &str, utf16string: &Utf16St
         i += 1;
     }
     let mut utf8offset2 = 0;
-    while let Some((j, _)) = chars.next() {
+    for (j, _) in chars {
         if i == offset2 {
             utf8offset2 = j;
             break;
@@ -126,4 +169,148 @@ pub fn two_utf16_offsets_as_utf8_offsets(utf8string: &str, utf16string: &Utf16St
         i += 1;
     }
     (utf8offset1, utf8offset2)
+}
+
+/// Error produced when translating an offset between encodings.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum OffsetError {
+    /// The offset is past the end of the string.
+    OutOfBounds(usize),
+    /// The offset falls inside a code point.
+    NotCharBoundary(usize),
+    /// The offset is less than a previously translated offset.
+    Unsorted(usize),
+}
+
+impl core::fmt::Display for OffsetError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            OffsetError::OutOfBounds(offset) => write!(f, "offset {offset} is out of bounds"),
+            OffsetError::NotCharBoundary(offset) => write!(f, "offset {offset} is not a code point boundary"),
+            OffsetError::Unsorted(offset) => write!(f, "offset {offset} is less than a previous offset"),
+        }
+    }
+}
+
+impl core::error::Error for OffsetError {}
+
+/// Translates ascending UTF-8 offsets into UTF-16 offsets, walking the
+/// string once. Only the UTF-8 string is required.
+pub fn utf8_offsets_as_utf16_offsets<I: IntoIterator<Item = usize>>(utf8string: &str, offsets: I) -> Utf8OffsetsAsUtf16Offsets<'_, I::IntoIter> {
+    Utf8OffsetsAsUtf16Offsets {
+        string: utf8string,
+        offsets: offsets.into_iter(),
+        utf8offset: 0,
+        utf16offset: 0,
+    }
+}
+
+/// Translates ascending UTF-16 offsets into UTF-8 offsets, walking the
+/// string once. Only the UTF-16 string is required.
+///
+/// A lone surrogate is counted as U+FFFD, that is, three UTF-8 bytes.
+pub fn utf16_offsets_as_utf8_offsets<I: IntoIterator<Item = usize>>(utf16string: &Utf16Str, offsets: I) -> Utf16OffsetsAsUtf8Offsets<'_, I::IntoIter> {
+    Utf16OffsetsAsUtf8Offsets {
+        string: utf16string,
+        offsets: offsets.into_iter(),
+        utf16offset: 0,
+        utf8offset: 0,
+    }
+}
+
+/// Iterator returned by [`utf8_offsets_as_utf16_offsets`].
+pub struct Utf8OffsetsAsUtf16Offsets<'a, I> {
+    string: &'a str,
+    offsets: I,
+    utf8offset: usize,
+    utf16offset: usize,
+}
+
+impl<I: Iterator<Item = usize>> Iterator for Utf8OffsetsAsUtf16Offsets<'_, I> {
+    type Item = Result<usize, OffsetError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offsets.next()?;
+        if offset > self.string.len() {
+            return Some(Err(OffsetError::OutOfBounds(offset)));
+        }
+        if offset < self.utf8offset {
+            return Some(Err(OffsetError::Unsorted(offset)));
+        }
+        if !self.string.is_char_boundary(offset) {
+            return Some(Err(OffsetError::NotCharBoundary(offset)));
+        }
+        for ch in self.string[self.utf8offset..offset].chars() {
+            self.utf16offset += ch.len_utf16();
+        }
+        self.utf8offset = offset;
+        Some(Ok(self.utf16offset))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+/// Iterator returned by [`utf16_offsets_as_utf8_offsets`].
+pub struct Utf16OffsetsAsUtf8Offsets<'a, I> {
+    string: &'a Utf16Str,
+    offsets: I,
+    utf16offset: usize,
+    utf8offset: usize,
+}
+
+impl<I: Iterator<Item = usize>> Iterator for Utf16OffsetsAsUtf8Offsets<'_, I> {
+    type Item = Result<usize, OffsetError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offsets.next()?;
+        let raw = &self.string.raw;
+        if offset > raw.len() {
+            return Some(Err(OffsetError::OutOfBounds(offset)));
+        }
+        if offset < self.utf16offset {
+            return Some(Err(OffsetError::Unsorted(offset)));
+        }
+        if offset > 0 && offset < raw.len() && is_high_surrogate(raw[offset - 1]) && is_low_surrogate(raw[offset]) {
+            return Some(Err(OffsetError::NotCharBoundary(offset)));
+        }
+        self.utf8offset += utf8_len_of_code_units(&raw[self.utf16offset..offset]);
+        self.utf16offset = offset;
+        Some(Ok(self.utf8offset))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+/// Offset conversions computed from a UTF-8 string alone.
+pub trait StrExt {
+    /// Returns the number of UTF-16 code units needed to encode the string.
+    fn utf16_len(&self) -> usize;
+
+    /// Returns the UTF-16 offset equivalent to the given UTF-8 offset.
+    fn utf16_offset_of(&self, utf8offset: usize) -> Result<usize, OffsetError>;
+
+    /// Returns the UTF-16 range equivalent to the given UTF-8 range.
+    fn utf16_range_of(&self, utf8range: Range<usize>) -> Result<Range<usize>, OffsetError>;
+}
+
+impl StrExt for str {
+    fn utf16_len(&self) -> usize {
+        // one unit per lead byte, and one more for each four-byte sequence
+        self.bytes().map(|b| (b & 0xC0 != 0x80) as usize + (b >= 0xF0) as usize).sum()
+    }
+
+    fn utf16_offset_of(&self, utf8offset: usize) -> Result<usize, OffsetError> {
+        utf8_offsets_as_utf16_offsets(self, [utf8offset]).next().unwrap()
+    }
+
+    fn utf16_range_of(&self, utf8range: Range<usize>) -> Result<Range<usize>, OffsetError> {
+        let mut offsets = utf8_offsets_as_utf16_offsets(self, [utf8range.start, utf8range.end]);
+        let start = offsets.next().unwrap()?;
+        let end = offsets.next().unwrap()?;
+        Ok(start..end)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,333 @@
+use crate::{utils::*, Utf16Str};
+use core::ops::BitOr;
+
+impl Utf16Str {
+    /// Returns the string without leading and trailing Unicode
+    /// `White_Space`, as `str::trim` does.
+    pub fn trim(&self) -> &Utf16Str {
+        self.trim_code_units(is_whitespace, true, true)
+    }
+
+    /// Returns the string without leading Unicode `White_Space`.
+    pub fn trim_start(&self) -> &Utf16Str {
+        self.trim_code_units(is_whitespace, true, false)
+    }
+
+    /// Returns the string without trailing Unicode `White_Space`.
+    pub fn trim_end(&self) -> &Utf16Str {
+        self.trim_code_units(is_whitespace, false, true)
+    }
+
+    /// Returns the string without leading and trailing ECMAScript
+    /// `WhiteSpace` and `LineTerminator`, as `String.prototype.trim` does.
+    /// Unlike [`trim`](Self::trim), this removes U+FEFF and keeps U+0085.
+    pub fn trim_js(&self) -> &Utf16Str {
+        self.trim_code_units(is_js_whitespace, true, true)
+    }
+
+    /// Returns the string without leading ECMAScript whitespace, as
+    /// `String.prototype.trimStart` does.
+    pub fn trim_start_js(&self) -> &Utf16Str {
+        self.trim_code_units(is_js_whitespace, true, false)
+    }
+
+    /// Returns the string without trailing ECMAScript whitespace, as
+    /// `String.prototype.trimEnd` does.
+    pub fn trim_end_js(&self) -> &Utf16Str {
+        self.trim_code_units(is_js_whitespace, false, true)
+    }
+
+    /// Returns the string without leading and trailing code points that
+    /// match a pattern. Lone surrogates never match.
+    pub fn trim_matches<P: CharPattern>(&self, mut pattern: P) -> &Utf16Str {
+        self.trim_start_matches(|ch| pattern.matches(ch)).trim_end_matches(pattern)
+    }
+
+    /// Returns the string without leading code points that match a
+    /// pattern. Lone surrogates never match.
+    pub fn trim_start_matches<P: CharPattern>(&self, mut pattern: P) -> &Utf16Str {
+        let mut start = 0;
+        for result in char::decode_utf16(self.raw.iter().copied()) {
+            match result {
+                Ok(ch) if pattern.matches(ch) => start += ch.len_utf16(),
+                _ => break,
+            }
+        }
+        &self[start..]
+    }
+
+    /// Returns the string without trailing code points that match a
+    /// pattern. Lone surrogates never match.
+    pub fn trim_end_matches<P: CharPattern>(&self, mut pattern: P) -> &Utf16Str {
+        let mut end = self.len();
+        while end > 0 {
+            let cu2 = self.raw[end - 1];
+            let (ch, len) = if end > 1 && is_low_surrogate(cu2) && is_high_surrogate(self.raw[end - 2]) {
+                (decode_char(self.raw[end - 2], cu2), 2)
+            } else if let Some(ch) = char::from_u32(cu2 as u32) {
+                (ch, 1)
+            } else {
+                break;
+            };
+            if !pattern.matches(ch) {
+                break;
+            }
+            end -= len;
+        }
+        &self[..end]
+    }
+
+    /// Splits the string by Unicode `White_Space`, skipping empty pieces,
+    /// as `str::split_whitespace` does.
+    pub fn split_whitespace(&self) -> Utf16SplitWhitespace<'_> {
+        Utf16SplitWhitespace { rest: self }
+    }
+
+    /// Splits the string by ASCII whitespace, skipping empty pieces, as
+    /// `str::split_ascii_whitespace` does.
+    pub fn split_ascii_whitespace(&self) -> Utf16SplitAsciiWhitespace<'_> {
+        Utf16SplitAsciiWhitespace { rest: self }
+    }
+
+    /// Iterates the lines of the string without their terminators. A
+    /// trailing terminator does not start an empty line.
+    ///
+    /// Lines end at `\n` and `\r\n`, as in `str::lines`; use
+    /// [`Utf16Lines::terminators`] to select others.
+    pub fn lines(&self) -> Utf16Lines<'_> {
+        Utf16Lines {
+            rest: self,
+            terminators: LineTerminators::STD,
+        }
+    }
+
+    /// Iterates the lines of the string including their terminators.
+    ///
+    /// Lines end at `\n` and `\r\n`; use
+    /// [`Utf16LinesWithTerminators::terminators`] to select others.
+    pub fn lines_with_terminators(&self) -> Utf16LinesWithTerminators<'_> {
+        Utf16LinesWithTerminators {
+            rest: self,
+            terminators: LineTerminators::STD,
+        }
+    }
+
+    fn trim_code_units(&self, is_trimmed: fn(u16) -> bool, start: bool, end: bool) -> &Utf16Str {
+        let mut raw = &self.raw;
+        if start {
+            let i = raw.iter().position(|cu| !is_trimmed(*cu)).unwrap_or(raw.len());
+            raw = &raw[i..];
+        }
+        if end {
+            let i = raw.iter().rposition(|cu| !is_trimmed(*cu)).map_or(0, |i| i + 1);
+            raw = &raw[..i];
+        }
+        unsafe { Utf16Str::from_utf16_unchecked(raw) }
+    }
+}
+
+/// Determines whether a code unit is Unicode `White_Space`, all of which
+/// is in the Basic Multilingual Plane.
+fn is_whitespace(cu: u16) -> bool {
+    char::from_u32(cu as u32).is_some_and(char::is_whitespace)
+}
+
+fn is_ascii_whitespace(cu: u16) -> bool {
+    cu < 0x80 && (cu as u8).is_ascii_whitespace()
+}
+
+/// A pattern of code points for [`Utf16Str::trim_matches`].
+///
+/// Implemented for `char`, slices and arrays of `char`, and closures
+/// taking a `char`.
+pub trait CharPattern {
+    fn matches(&mut self, ch: char) -> bool;
+}
+
+impl CharPattern for char {
+    #[inline]
+    fn matches(&mut self, ch: char) -> bool {
+        *self == ch
+    }
+}
+
+impl CharPattern for &[char] {
+    #[inline]
+    fn matches(&mut self, ch: char) -> bool {
+        self.contains(&ch)
+    }
+}
+
+impl<const N: usize> CharPattern for [char; N] {
+    #[inline]
+    fn matches(&mut self, ch: char) -> bool {
+        self.contains(&ch)
+    }
+}
+
+impl<const N: usize> CharPattern for &[char; N] {
+    #[inline]
+    fn matches(&mut self, ch: char) -> bool {
+        self.contains(&ch)
+    }
+}
+
+impl<F: FnMut(char) -> bool> CharPattern for F {
+    #[inline]
+    fn matches(&mut self, ch: char) -> bool {
+        self(ch)
+    }
+}
+
+/// Iterator over the pieces of a [`Utf16Str`] between Unicode whitespace.
+#[derive(Clone, Debug)]
+pub struct Utf16SplitWhitespace<'a> {
+    rest: &'a Utf16Str,
+}
+
+impl<'a> Iterator for Utf16SplitWhitespace<'a> {
+    type Item = &'a Utf16Str;
+
+    fn next(&mut self) -> Option<&'a Utf16Str> {
+        next_piece(&mut self.rest, is_whitespace)
+    }
+}
+
+/// Iterator over the pieces of a [`Utf16Str`] between ASCII whitespace.
+#[derive(Clone, Debug)]
+pub struct Utf16SplitAsciiWhitespace<'a> {
+    rest: &'a Utf16Str,
+}
+
+impl<'a> Iterator for Utf16SplitAsciiWhitespace<'a> {
+    type Item = &'a Utf16Str;
+
+    fn next(&mut self) -> Option<&'a Utf16Str> {
+        next_piece(&mut self.rest, is_ascii_whitespace)
+    }
+}
+
+fn next_piece<'a>(rest: &mut &'a Utf16Str, is_separator: fn(u16) -> bool) -> Option<&'a Utf16Str> {
+    let start = rest.raw.iter().position(|cu| !is_separator(*cu))?;
+    let end = rest.raw[start..].iter().position(|cu| is_separator(*cu)).map_or(rest.len(), |i| start + i);
+    let piece = &rest[start..end];
+    *rest = &rest[end..];
+    Some(piece)
+}
+
+/// A set of line terminators for [`Utf16Str::lines`] and
+/// [`Utf16Str::lines_with_terminators`].
+///
+/// ```
+/// use hydroperx_utf16::{LineTerminators, Utf16String};
+///
+/// let text = Utf16String::from("a\rb\u{2028}c");
+/// assert_eq!(text.lines().count(), 1);
+/// assert_eq!(text.lines().terminators(LineTerminators::ECMASCRIPT).count(), 3);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LineTerminators(u8);
+
+impl LineTerminators {
+    pub const LF: Self = Self(1);
+    pub const CR_LF: Self = Self(2);
+    pub const CR: Self = Self(4);
+    /// U+2028 LINE SEPARATOR.
+    pub const LINE_SEPARATOR: Self = Self(8);
+    /// U+2029 PARAGRAPH SEPARATOR.
+    pub const PARAGRAPH_SEPARATOR: Self = Self(16);
+    /// `\n` and `\r\n`, as recognized by `str::lines`.
+    pub const STD: Self = Self(1 | 2);
+    /// Every line terminator of ECMAScript.
+    pub const ECMASCRIPT: Self = Self(1 | 2 | 4 | 8 | 16);
+
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the length in code units of the terminator at the start of
+    /// `raw`, if any.
+    fn match_at(self, raw: &[u16]) -> Option<usize> {
+        match raw {
+            [0x0D, 0x0A, ..] if self.contains(Self::CR_LF) => Some(2),
+            [0x0D, ..] if self.contains(Self::CR) => Some(1),
+            [0x0A, ..] if self.contains(Self::LF) => Some(1),
+            [0x2028, ..] if self.contains(Self::LINE_SEPARATOR) => Some(1),
+            [0x2029, ..] if self.contains(Self::PARAGRAPH_SEPARATOR) => Some(1),
+            _ => None,
+        }
+    }
+}
+
+impl BitOr for LineTerminators {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+/// Iterator over the lines of a [`Utf16Str`] without their terminators.
+#[derive(Clone, Debug)]
+pub struct Utf16Lines<'a> {
+    rest: &'a Utf16Str,
+    terminators: LineTerminators,
+}
+
+impl<'a> Utf16Lines<'a> {
+    /// Selects the line terminators to recognize.
+    pub fn terminators(self, terminators: LineTerminators) -> Self {
+        Self { terminators, ..self }
+    }
+}
+
+impl<'a> Iterator for Utf16Lines<'a> {
+    type Item = &'a Utf16Str;
+
+    fn next(&mut self) -> Option<&'a Utf16Str> {
+        next_line(&mut self.rest, self.terminators).map(|(line, terminator_len)| &line[..line.len() - terminator_len])
+    }
+}
+
+/// Iterator over the lines of a [`Utf16Str`] including their terminators.
+#[derive(Clone, Debug)]
+pub struct Utf16LinesWithTerminators<'a> {
+    rest: &'a Utf16Str,
+    terminators: LineTerminators,
+}
+
+impl<'a> Utf16LinesWithTerminators<'a> {
+    /// Selects the line terminators to recognize.
+    pub fn terminators(self, terminators: LineTerminators) -> Self {
+        Self { terminators, ..self }
+    }
+}
+
+impl<'a> Iterator for Utf16LinesWithTerminators<'a> {
+    type Item = &'a Utf16Str;
+
+    fn next(&mut self) -> Option<&'a Utf16Str> {
+        next_line(&mut self.rest, self.terminators).map(|(line, _)| line)
+    }
+}
+
+/// Splits off the next line including its terminator, and returns it with
+/// the length of the terminator.
+fn next_line<'a>(rest: &mut &'a Utf16Str, terminators: LineTerminators) -> Option<(&'a Utf16Str, usize)> {
+    if rest.is_empty() {
+        return None;
+    }
+    let (end, terminator_len) = (0..rest.len())
+        .find_map(|i| terminators.match_at(&rest.raw[i..]).map(|len| (i + len, len)))
+        .unwrap_or((rest.len(), 0));
+    let line = &rest[..end];
+    *rest = &rest[end..];
+    Some((line, terminator_len))
+}
\ No newline at end of file
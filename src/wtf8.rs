@@ -0,0 +1,278 @@
+//! WTF-8, a superset of UTF-8 that round-trips ill-formed UTF-16.
+//!
+//! Surrogate pairs are encoded as the four-byte sequence of their code
+//! point, as in UTF-8, while lone surrogates are encoded as three-byte
+//! sequences. A string without lone surrogates therefore encodes to plain
+//! UTF-8.
+//!
+//! ```
+//! use hydroperx_utf16::{Utf16Str, Utf16String};
+//!
+//! let string = unsafe { Utf16Str::from_utf16_unchecked(&[0x61, 0xD800]) };
+//! let wtf8 = string.to_wtf8();
+//! assert_eq!(wtf8.as_bytes(), b"a\xED\xA0\x80");
+//! assert_eq!(wtf8.as_str(), None);
+//! assert_eq!(Utf16String::from_wtf8(wtf8.as_bytes()).unwrap(), string);
+//! ```
+
+use core::fmt::{self, Write};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{utils::*, Utf16Str, Utf16String};
+
+/// An owned WTF-8 string.
+#[derive(Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Wtf8Buf {
+    bytes: Vec<u8>,
+}
+
+impl Wtf8Buf {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encodes UTF-16 code units, preserving lone surrogates.
+    pub fn from_utf16(string: &Utf16Str) -> Self {
+        let mut r = Self { bytes: Vec::with_capacity(string.len()) };
+        r.push_utf16_str(string);
+        r
+    }
+
+    /// Validates WTF-8 bytes.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, Wtf8Error> {
+        decode(&bytes, |_| ())?;
+        Ok(Self { bytes })
+    }
+
+    /// Takes bytes without validating them.
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be valid WTF-8: UTF-8 in which three-byte sequences may
+    /// also encode surrogates, except for a high surrogate followed by a
+    /// low surrogate.
+    pub unsafe fn from_bytes_unchecked(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns the number of bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns the string as UTF-8, or `None` if it contains a lone
+    /// surrogate.
+    pub fn as_str(&self) -> Option<&str> {
+        core::str::from_utf8(&self.bytes).ok()
+    }
+
+    /// Converts into UTF-8, or returns the string back if it contains a
+    /// lone surrogate.
+    pub fn into_string(self) -> Result<String, Self> {
+        String::from_utf8(self.bytes).map_err(|e| Self { bytes: e.into_bytes() })
+    }
+
+    /// Decodes into UTF-16, restoring lone surrogates exactly.
+    pub fn to_utf16_string(&self) -> Utf16String {
+        let mut buf = Vec::with_capacity(self.bytes.len());
+        decode(&self.bytes, |cu| buf.push(cu)).expect("Wtf8Buf holds valid WTF-8");
+        Utf16String { buf }
+    }
+
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]));
+    }
+
+    #[inline]
+    pub fn push_str(&mut self, string: &str) {
+        self.bytes.extend_from_slice(string.as_bytes());
+    }
+
+    /// Appends UTF-16 code units. A low surrogate at the start of `string`
+    /// pairs with a high surrogate at the end of `self`.
+    pub fn push_utf16_str(&mut self, string: &Utf16Str) {
+        let mut raw = &string.raw;
+        if let Some(&cu2) = raw.first()
+            && is_low_surrogate(cu2)
+            && let Some(cu1) = self.trailing_high_surrogate()
+        {
+            self.bytes.truncate(self.bytes.len() - 3);
+            self.push(decode_char(cu1, cu2));
+            raw = &raw[1..];
+        }
+        for result in char::decode_utf16(raw.iter().copied()) {
+            match result {
+                Ok(ch) => self.push(ch),
+                Err(e) => {
+                    let cu = e.unpaired_surrogate();
+                    self.bytes.extend_from_slice(&[0xE0 | (cu >> 12) as u8, 0x80 | ((cu >> 6) & 0x3F) as u8, 0x80 | (cu & 0x3F) as u8]);
+                }
+            }
+        }
+    }
+
+    fn trailing_high_surrogate(&self) -> Option<u16> {
+        match self.bytes[self.bytes.len().checked_sub(3)?..] {
+            [0xED, b2 @ 0xA0..=0xAF, b3] => Some(0xD000 | ((b2 & 0x3F) as u16) << 6 | (b3 & 0x3F) as u16),
+            _ => None,
+        }
+    }
+}
+
+impl Utf16Str {
+    /// Encodes into WTF-8, preserving lone surrogates.
+    pub fn to_wtf8(&self) -> Wtf8Buf {
+        Wtf8Buf::from_utf16(self)
+    }
+}
+
+impl Utf16String {
+    /// Decodes WTF-8, restoring lone surrogates exactly. Valid UTF-8 is
+    /// valid WTF-8.
+    pub fn from_wtf8(bytes: &[u8]) -> Result<Utf16String, Wtf8Error> {
+        let mut buf = Vec::with_capacity(bytes.len());
+        decode(bytes, |cu| buf.push(cu))?;
+        Ok(Utf16String { buf })
+    }
+}
+
+/// Decodes WTF-8 into code units.
+fn decode(bytes: &[u8], mut push: impl FnMut(u16)) -> Result<(), Wtf8Error> {
+    let mut i = 0;
+    let mut after_high_surrogate = false;
+    while i < bytes.len() {
+        let error = Wtf8Error { valid_up_to: i };
+        let continuation = |n: usize, range: core::ops::RangeInclusive<u8>| match bytes.get(i + n) {
+            Some(c) if range.contains(c) => Ok((c & 0x3F) as u32),
+            _ => Err(error),
+        };
+        let b = bytes[i];
+        let (cp, len) = match b {
+            0..=0x7F => (b as u32, 1),
+            0xC2..=0xDF => (((b & 0x1F) as u32) << 6 | continuation(1, 0x80..=0xBF)?, 2),
+            0xE0..=0xEF => {
+                let min = if b == 0xE0 { 0xA0 } else { 0x80 };
+                (((b & 0x0F) as u32) << 12 | continuation(1, min..=0xBF)? << 6 | continuation(2, 0x80..=0xBF)?, 3)
+            }
+            0xF0..=0xF4 => {
+                let (min, max) = match b {
+                    0xF0 => (0x90, 0xBF),
+                    0xF4 => (0x80, 0x8F),
+                    _ => (0x80, 0xBF),
+                };
+                let cp = ((b & 0x07) as u32) << 18
+                    | continuation(1, min..=max)? << 12
+                    | continuation(2, 0x80..=0xBF)? << 6
+                    | continuation(3, 0x80..=0xBF)?;
+                (cp, 4)
+            }
+            _ => return Err(error),
+        };
+        // a surrogate pair must be encoded as a single code point
+        if after_high_surrogate && (0xDC00..=0xDFFF).contains(&cp) {
+            return Err(error);
+        }
+        after_high_surrogate = (0xD800..=0xDBFF).contains(&cp);
+        if cp >= 0x10000 {
+            let cp = cp - 0x10000;
+            push(0xD800 | (cp >> 10) as u16);
+            push(0xDC00 | (cp & 0x3FF) as u16);
+        } else {
+            push(cp as u16);
+        }
+        i += len;
+    }
+    Ok(())
+}
+
+impl From<&Utf16Str> for Wtf8Buf {
+    fn from(value: &Utf16Str) -> Self {
+        Wtf8Buf::from_utf16(value)
+    }
+}
+
+impl From<&str> for Wtf8Buf {
+    fn from(value: &str) -> Self {
+        Self { bytes: value.as_bytes().to_vec() }
+    }
+}
+
+impl From<String> for Wtf8Buf {
+    fn from(value: String) -> Self {
+        Self { bytes: value.into_bytes() }
+    }
+}
+
+impl From<&Wtf8Buf> for Utf16String {
+    fn from(value: &Wtf8Buf) -> Self {
+        value.to_utf16_string()
+    }
+}
+
+impl AsRef<[u8]> for Wtf8Buf {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl fmt::Display for Wtf8Buf {
+    /// Writes lone surrogates as U+FFFD.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rest = &self.bytes[..];
+        // 0xED is always a lead byte, followed by 0xA0 or more only for
+        // surrogates
+        while let Some(i) = rest.windows(2).position(|w| w[0] == 0xED && w[1] >= 0xA0) {
+            f.write_str(unsafe { core::str::from_utf8_unchecked(&rest[..i]) })?;
+            f.write_char(char::REPLACEMENT_CHARACTER)?;
+            rest = &rest[i + 3..];
+        }
+        f.write_str(unsafe { core::str::from_utf8_unchecked(rest) })
+    }
+}
+
+impl fmt::Debug for Wtf8Buf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_utf16_string(), f)
+    }
+}
+
+/// Error produced when bytes are not valid WTF-8.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Wtf8Error {
+    valid_up_to: usize,
+}
+
+impl Wtf8Error {
+    /// Returns the length of the valid prefix of the input.
+    #[inline]
+    pub fn valid_up_to(&self) -> usize {
+        self.valid_up_to
+    }
+}
+
+impl fmt::Display for Wtf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid WTF-8 sequence at offset {}", self.valid_up_to)
+    }
+}
+
+impl core::error::Error for Wtf8Error {}
\ No newline at end of file
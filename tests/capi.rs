@@ -0,0 +1,50 @@
+//! Checks the C API: that `capi/hydroperx_utf16.h` is the header cbindgen
+//! generates, and that `capi/test.c` works against the static library.
+
+#![cfg(feature = "capi")]
+
+use std::path::Path;
+
+/// Compares the checked-in header with the one `build.rs` generates. To
+/// update it, copy the generated header over it.
+#[test]
+fn test_capi_header() {
+    let generated = env!("HYDROPERX_UTF16_HEADER");
+    let checked_in = Path::new(env!("CARGO_MANIFEST_DIR")).join("capi/hydroperx_utf16.h");
+    assert_eq!(
+        std::fs::read_to_string(&checked_in).unwrap(),
+        std::fs::read_to_string(generated).unwrap(),
+        "capi/hydroperx_utf16.h is out of date with src/capi.rs; copy {generated} over it",
+    );
+}
+
+/// Builds the crate as a static library and runs `capi/test.c` against it.
+#[cfg(unix)]
+#[test]
+fn test_capi() {
+    use std::process::Command;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let exe = std::env::current_exe().unwrap();
+    let target_dir = exe.parent().unwrap().parent().unwrap().join("capi");
+    let status = Command::new(env!("CARGO"))
+        .current_dir(root)
+        .args(["rustc", "--lib", "--features", "capi", "--crate-type", "staticlib", "--target-dir"])
+        .arg(&target_dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let program = target_dir.join("capi_test");
+    let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".into()))
+        .args(["-std=c99", "-Wall", "-Werror", "-I"])
+        .arg(root.join("capi"))
+        .arg(root.join("capi/test.c"))
+        .arg(target_dir.join("debug/libhydroperx_utf16.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&program)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert!(Command::new(&program).status().unwrap().success());
+}
\ No newline at end of file
@@ -0,0 +1,56 @@
+//! Checks that `SmallUtf16String` frees its heap buffer when moving inline,
+//! with an allocator that counts the bytes live on the current thread.
+
+use hydroperx_utf16::{SmallUtf16String, Utf16String};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAlloc;
+
+thread_local! {
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE_BYTES.with(|n| n.set(n.get() + layout.size() as isize));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.with(|n| n.set(n.get() - layout.size() as isize));
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+fn live_bytes() -> isize {
+    LIVE_BYTES.with(Cell::get)
+}
+
+#[test]
+fn test_moving_inline_frees_heap_buffer() {
+    let before = live_bytes();
+    let string = SmallUtf16String::from(Utf16String::from("short"));
+    assert!(string.is_inline());
+    assert_eq!(live_bytes(), before);
+    drop(string);
+
+    let mut string = SmallUtf16String::from("a string that does not fit inline");
+    assert!(!string.is_inline());
+    while string.len() > 5 {
+        string.pop();
+    }
+    string.shrink_to_fit();
+    assert!(string.is_inline());
+    assert_eq!(string, "a str");
+    assert_eq!(live_bytes(), before);
+    drop(string);
+
+    let string = SmallUtf16String::from(Utf16String::from("a string that does not fit inline"));
+    assert!(!string.is_inline());
+    drop(string);
+    assert_eq!(live_bytes(), before);
+}
\ No newline at end of file